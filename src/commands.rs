@@ -1,41 +1,128 @@
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
+use sha2::{Digest, Sha512};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
-use crate::config::Config;
+use crate::config::{AutoSetCurrent, Config, KeyKind};
+use crate::export;
 use crate::github;
-use crate::version::{GodotVersion, VersionQuery};
+use crate::import::{self, ImportMode};
+use crate::link;
+use crate::matrix::{self, Outcome};
+use crate::process;
+use crate::project;
+use crate::settings;
+use crate::snapshot;
+use crate::trash;
+use crate::version::{is_valid_label, Channel, GodotVersion, VersionQuery, VersionSet};
+
+/// Flags of `godo run`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions<'a> {
+    pub mono: Option<bool>,
+    pub latest: bool,
+    pub log: Option<bool>,
+    /// Open this project in the editor, after checking that the engine suits it
+    pub project: Option<&'a Path>,
+    pub allow_upgrade: bool,
+    pub snapshot: bool,
+}
+
+/// Flags of `godo install` that apply to every version being installed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InstallOptions<'a> {
+    pub mono: Option<bool>,
+    pub silent: bool,
+    pub from_file: Option<&'a Path>,
+    pub set_current: Option<AutoSetCurrent>,
+    pub channel: Option<Channel>,
+    pub self_contained: bool,
+    /// Install even when the official checksums cannot be downloaded
+    pub skip_checksum: bool,
+}
+
+pub fn install(config: &Config, versions: &[String], options: InstallOptions) -> Result<()> {
+    if let [version] = versions {
+        return install_one(config, version, options);
+    }
+    if options.from_file.is_some() {
+        bail!("--from-file installs a single version");
+    }
+
+    // Ask about Mono once for the whole batch, unless every version names it with `-mono`
+    let named = versions
+        .iter()
+        .all(|v| VersionQuery::from_input(v).is_some_and(|q| q.mono.is_some()));
+    let mono = match options.mono.or(config.default_mono.choice()) {
+        Some(m) => Some(m),
+        None if named => None,
+        None => Some(config.prompter.choose_mono("Install")?),
+    };
+    let options = InstallOptions { mono, ..options };
+
+    let mut failed = 0;
+    for version in versions {
+        println!("{} {}", "Installing".dimmed(), version.bold());
+        if let Err(e) = install_one(config, version, options) {
+            eprintln!("  {} {version}: {e}", "!".red().bold());
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        bail!("{failed} of {} installs failed", versions.len());
+    }
+    Ok(())
+}
+
+fn install_one(config: &Config, version: &str, options: InstallOptions) -> Result<()> {
+    let set_current = resolve_set_current(config, options.set_current)?;
+    if let Some(archive) = options.from_file {
+        return install_from_file(config, version, options, archive, set_current);
+    }
 
-pub fn install(config: &Config, version: &str, mono: Option<bool>, silent: bool) -> Result<()> {
     let query = VersionQuery::from_input(version).context("Invalid version format")?;
 
+    // A `-mono` suffix on the version is as explicit as the flag
+    let mono = query.mono.or(options.mono).or(config.default_mono.choice());
     let mono_flag_provided = mono.is_some();
     let mono = match mono {
         Some(m) => m,
-        None => ask_mono()?,
+        None => config.prompter.choose_mono("Install")?,
     };
 
     println!("{}", "Fetching releases...".dimmed());
     let releases = github::fetch_releases_cached(config)?;
-    let release = github::find_matching_release(&releases, &query)?;
-    let ver = GodotVersion::from_tag(&release.tag_name).context("Failed to parse release tag")?;
+    let channel = options.channel.unwrap_or(config.default_channel);
+    let release = github::find_matching_release(&releases, &query, channel)?;
+    let ver = release.version().context("Failed to parse release tag")?;
     let ver_mono = GodotVersion {
         mono,
         ..ver.clone()
     };
 
-    let need_confirm = !silent || !mono_flag_provided;
+    let need_confirm = !options.silent || !mono_flag_provided;
     if need_confirm {
         println!("  Found version: {}", format!("{ver_mono}").green().bold());
-        if !ask_yes_no("Install this version?")? {
+        if !config
+            .prompter
+            .confirm("Install this version?", "--yes (or --silent with --mono)")?
+        {
             println!("{}", "Installation cancelled.".yellow());
             return Ok(());
         }
     }
 
-    let assets = github::find_platform_assets(&release.assets, mono)?;
+    let pattern = release
+        .source
+        .as_deref()
+        .and_then(|name| config.source(name))
+        .and_then(|s| s.asset_pattern.as_deref());
+    let assets = github::find_platform_assets(&release.assets, mono, pattern)?;
 
     let version_dir = config.engine_dir.join(ver_mono.folder_name());
     if version_dir.exists() {
@@ -49,18 +136,19 @@ pub fn install(config: &Config, version: &str, mono: Option<bool>, silent: bool)
     std::fs::create_dir_all(&config.temp_dir).context("Failed to create temp directory")?;
     std::fs::create_dir_all(&config.engine_dir).context("Failed to create engine directory")?;
 
-    let main_zip = download_with_progress(
-        &assets.main_asset.browser_download_url,
-        &config.temp_dir.join(&assets.main_asset.name),
-        assets.main_asset.size,
-    )?;
+    let checksums = match fetch_checksums(config, &release.assets) {
+        Err(e) if options.skip_checksum => {
+            eprintln!("  {} {e:#}, installing unverified", "!".yellow());
+            None
+        }
+        checksums => checksums.map_err(|e| {
+            anyhow!("{e:#}. Pass --skip-checksum to install without verifying the download")
+        })?,
+    };
+    let main_zip = fetch_archive(config, assets.main_asset, checksums.as_ref())?;
 
     let console_zip = if let Some(console_asset) = assets.console_asset {
-        Some(download_with_progress(
-            &console_asset.browser_download_url,
-            &config.temp_dir.join(&console_asset.name),
-            console_asset.size,
-        )?)
+        Some(fetch_archive(config, console_asset, checksums.as_ref())?)
     } else {
         None
     };
@@ -74,20 +162,110 @@ pub fn install(config: &Config, version: &str, mono: Option<bool>, silent: bool)
 
     rename_executables(&version_dir)?;
 
-    cleanup_temp(&config.temp_dir, &assets.main_asset.name);
-    if let Some(ca) = assets.console_asset {
-        cleanup_temp(&config.temp_dir, &ca.name);
+    if !config.keep_archives {
+        cleanup_temp(&config.temp_dir, &assets.main_asset.name);
+        if let Some(ca) = assets.console_asset {
+            cleanup_temp(&config.temp_dir, &ca.name);
+        }
+    }
+
+    finish_install(config, &ver_mono, set_current, options.self_contained)
+}
+
+fn install_from_file(
+    config: &Config,
+    version: &str,
+    options: InstallOptions,
+    archive: &Path,
+    set_current: AutoSetCurrent,
+) -> Result<()> {
+    let ver = GodotVersion::from_input(version)
+        .context("--from-file needs a full version such as '4.3-stable'")?;
+    if !archive.is_file() {
+        bail!("Archive not found: {}", archive.display());
+    }
+
+    let mono = options.mono.unwrap_or_else(|| {
+        archive
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase().contains("mono"))
+            .unwrap_or(false)
+    });
+    let ver_mono = GodotVersion { mono, ..ver };
+
+    let version_dir = config.engine_dir.join(ver_mono.folder_name());
+    if version_dir.exists() {
+        bail!(
+            "Version {} is already installed at {}",
+            ver_mono,
+            version_dir.display()
+        );
+    }
+
+    std::fs::create_dir_all(&config.engine_dir).context("Failed to create engine directory")?;
+
+    println!("{}", "Extracting...".dimmed());
+    extract_zip_strip_prefix(archive, &version_dir)?;
+    rename_executables(&version_dir)?;
+
+    finish_install(config, &ver_mono, set_current, options.self_contained)
+}
+
+/// Settles an `ask` for non-interactive runs before anything is downloaded, so a
+/// script never fails halfway through an install.
+fn resolve_set_current(
+    config: &Config,
+    set_current: Option<AutoSetCurrent>,
+) -> Result<AutoSetCurrent> {
+    let set_current = set_current.unwrap_or(config.auto_set_current);
+    if set_current != AutoSetCurrent::Ask || config.prompter.is_interactive() {
+        return Ok(set_current);
+    }
+    if config.prompter.assumes_yes() {
+        return Ok(AutoSetCurrent::Always);
+    }
+    // The question is only asked when another version is already installed
+    if get_installed_versions(config)?.is_empty() {
+        return Ok(set_current);
     }
+    bail!("Cannot ask whether to set the new version as current without a terminal. Pass --set-current or set auto_set_current.")
+}
 
+fn finish_install(
+    config: &Config,
+    ver_mono: &GodotVersion,
+    set_current: AutoSetCurrent,
+    self_contained: bool,
+) -> Result<()> {
+    if self_contained {
+        settings::set_self_contained(&config.engine_dir.join(ver_mono.folder_name()), true)?;
+    }
     println!(
         "  {} {}",
         "✓".green(),
         format!("Installed {ver_mono}").green().bold()
     );
+    offer_current(config, ver_mono, set_current)
+}
 
+/// Makes a newly added version current as `set_current` says. The first version always is.
+fn offer_current(
+    config: &Config,
+    ver_mono: &GodotVersion,
+    set_current: AutoSetCurrent,
+) -> Result<()> {
     let installed = get_installed_versions(config)?;
     if installed.len() > 1 {
-        if ask_yes_no("Set this version as current?")? {
+        let current = read_current_link(config).and_then(|f| GodotVersion::from_folder(&f));
+        let make_current = match set_current {
+            AutoSetCurrent::Always => true,
+            AutoSetCurrent::Never => false,
+            AutoSetCurrent::Ask => config
+                .prompter
+                .confirm("Set this version as current?", "--set-current")?,
+            AutoSetCurrent::IfNewer => current.is_none_or(|c| *ver_mono > c),
+        };
+        if make_current {
             update_current_symlink(config, &ver_mono.folder_name())?;
             println!(
                 "  {} Current version set to {}",
@@ -104,112 +282,1654 @@ pub fn install(config: &Config, version: &str, mono: Option<bool>, silent: bool)
         );
     }
 
-    Ok(())
-}
+    Ok(())
+}
+
+/// Registers an engine that lives outside the engine directory, e.g. a custom build or the
+/// Steam version, by linking it into the engine directory.
+pub fn link(
+    config: &Config,
+    path: &Path,
+    name: Option<&str>,
+    set_current: Option<AutoSetCurrent>,
+) -> Result<()> {
+    let target = link::find_target(path)?;
+    let detected = link::detect_version(&link::executable_of(&target));
+    let label_of = |build: Option<&String>| {
+        build
+            .map(|b| link::default_label(b))
+            .unwrap_or_else(|| link::DEFAULT_LABEL.to_string())
+    };
+
+    let version = match (name, detected) {
+        (Some(name), detected) if is_valid_label(name) => {
+            let (ver, _) = detected.with_context(|| {
+                format!(
+                    "Could not detect the version of {}. Pass --as <version>+<label>, e.g. --as 4.3-stable+{name}",
+                    target.display()
+                )
+            })?;
+            GodotVersion {
+                label: Some(name.to_string()),
+                ..ver
+            }
+        }
+        (Some(name), detected) => {
+            let ver = GodotVersion::from_input(name).with_context(|| {
+                format!("Invalid name '{name}'. Use a label such as 'mybuild' or a version such as '4.3-stable+mybuild'")
+            })?;
+            GodotVersion {
+                mono: detected.as_ref().is_some_and(|(d, _)| d.mono),
+                label: ver
+                    .label
+                    .or_else(|| Some(label_of(detected.as_ref().map(|(_, b)| b)))),
+                ..ver
+            }
+        }
+        (None, Some((ver, build))) => GodotVersion {
+            label: Some(label_of(Some(&build))),
+            ..ver
+        },
+        (None, None) => bail!(
+            "Could not detect the version of {}. Name it with --as, e.g. --as 4.3-stable+mybuild",
+            target.display()
+        ),
+    };
+    let set_current = resolve_set_current(config, set_current)?;
+
+    let version_dir = config.engine_dir.join(version.folder_name());
+    if version_dir.exists() {
+        bail!(
+            "{version} already exists. Pick another name with --as, e.g. --as {}+other",
+            version.version_key()
+        );
+    }
+    link::create(&version_dir, &target)?;
+    println!(
+        "  {} {} {}",
+        "✓".green(),
+        format!("Linked {version}").green().bold(),
+        format!("→ {}", target.display()).dimmed()
+    );
+    offer_current(config, &version, set_current)
+}
+
+/// Finds engines installed by other tools or by hand and links, copies or moves them into
+/// the engine directory. Scans `paths`, or the usual locations when none are given.
+pub fn import(
+    config: &Config,
+    paths: &[PathBuf],
+    mode: Option<ImportMode>,
+    dry_run: bool,
+) -> Result<()> {
+    if mode.is_none() && !dry_run && !config.prompter.is_interactive() {
+        bail!("Cannot ask how to import without a terminal. Pass --mode link, copy or move");
+    }
+
+    let roots = if paths.is_empty() {
+        import::default_roots()
+    } else {
+        paths.to_vec()
+    };
+    println!("{}", "Scanning...".dimmed());
+    for root in &roots {
+        println!("  {}", root.display().to_string().dimmed());
+    }
+    let found = import::scan(&roots, &config.engine_dir);
+    if found.is_empty() {
+        println!("No Godot installs found.");
+        return Ok(());
+    }
+
+    let installed = get_installed_versions(config)?;
+    let mut imported = 0;
+    let mut failed = 0;
+    for engine in &found {
+        println!(
+            "  {} {} {}",
+            engine.version.to_string().green().bold(),
+            format!("({})", engine.build).dimmed(),
+            engine.target.display()
+        );
+
+        let linked = installed.iter().find(|v| {
+            link::target(&config.engine_dir.join(v.folder_name())).as_ref() == Some(&engine.target)
+        });
+        if let Some(linked) = linked {
+            println!("    {} Already linked as {linked}", "-".dimmed());
+            continue;
+        }
+        let existing = engine.import_as(ImportMode::Copy);
+        if installed.contains(&existing) {
+            println!("    {} {existing} is already installed", "-".dimmed());
+            continue;
+        }
+        if dry_run {
+            continue;
+        }
+
+        let mode = match mode {
+            Some(ImportMode::Move) if engine.is_steam() => {
+                println!(
+                    "    {} Steam manages this engine, link or copy it instead",
+                    "!".yellow()
+                );
+                continue;
+            }
+            Some(mode) => mode,
+            None => {
+                // Moving a Steam engine out of the library would break it in Steam
+                let mut choices = vec![
+                    ("Link", Some(ImportMode::Link)),
+                    ("Copy", Some(ImportMode::Copy)),
+                ];
+                if !engine.is_steam() {
+                    choices.push(("Move", Some(ImportMode::Move)));
+                }
+                choices.push(("Skip", None));
+                let labels: Vec<String> = choices.iter().map(|(l, _)| l.to_string()).collect();
+                let Some(picked) = config.prompter.pick(
+                    &format!("Import {}?", engine.version),
+                    &labels,
+                    &labels,
+                )?
+                else {
+                    println!("{}", "Cancelled.".yellow());
+                    break;
+                };
+                match choices[picked].1 {
+                    Some(mode) => mode,
+                    None => continue,
+                }
+            }
+        };
+
+        let version = engine.import_as(mode);
+        let version_dir = config.engine_dir.join(version.folder_name());
+        if version_dir.exists() {
+            println!("    {} {version} is already installed", "-".dimmed());
+            continue;
+        }
+        std::fs::create_dir_all(&config.engine_dir).context("Failed to create engine directory")?;
+        let result = match mode {
+            ImportMode::Link => link::create(&version_dir, &engine.target),
+            _ => import::transfer(
+                &engine.payload(&roots),
+                &engine.companions(&roots),
+                &version_dir,
+                mode,
+            )
+            .and_then(|_| rename_executables(&version_dir)),
+        };
+        match result {
+            Ok(()) => {
+                imported += 1;
+                println!(
+                    "    {} {}",
+                    "✓".green(),
+                    format!("Imported {version}").green().bold()
+                );
+            }
+            Err(e) => {
+                eprintln!("    {} {version}: {e:#}", "!".red().bold());
+                failed += 1;
+            }
+        }
+    }
+
+    if imported > 0 && read_current_link(config).is_none() {
+        println!(
+            "  Run {} to pick the version to use",
+            "godo current <version>".bold()
+        );
+    }
+    if failed > 0 {
+        bail!("{failed} imports failed");
+    }
+    Ok(())
+}
+
+pub fn rm(
+    config: &Config,
+    versions: &[String],
+    mono: Option<bool>,
+    silent: bool,
+    latest: bool,
+) -> Result<()> {
+    let mut targets: Vec<GodotVersion> = Vec::new();
+    let mut picked_all = true;
+    for version in versions {
+        let Some((target, picked)) =
+            resolve_installed(config, version, mono, Some(latest), "Remove")?
+        else {
+            println!("{}", "Removal cancelled.".yellow());
+            return Ok(());
+        };
+        picked_all &= picked;
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+
+    ensure_not_running(config, &targets)?;
+
+    let mono_flag_provided = picked_all || mono.is_some() || config.default_mono.choice().is_some();
+    let need_confirm = !silent || !mono_flag_provided;
+    if need_confirm {
+        let names: Vec<String> = targets.iter().map(|t| t.to_string()).collect();
+        println!("  Will remove: {}", names.join(", ").red().bold());
+        if !config
+            .prompter
+            .confirm("Continue?", "--yes (or --silent with --mono)")?
+        {
+            println!("{}", "Removal cancelled.".yellow());
+            return Ok(());
+        }
+    }
+
+    remove_versions(config, &targets)
+}
+
+pub fn prune(config: &Config, keep: usize, dry_run: bool) -> Result<()> {
+    let mut installed = get_installed_versions(config)?;
+    installed.sort_by(|a, b| b.cmp(a).then(a.mono.cmp(&b.mono)));
+    let current = read_current_link(config);
+
+    // Projects keep the newest install their pin matches
+    let mut pinned: Vec<(String, String)> = Vec::new();
+    for project in project::known_projects() {
+        let Some(pin) = project::read_pin(&project) else {
+            continue;
+        };
+        let Some(newest) = installed.iter().find(|v| pin.matches_loose(v)) else {
+            continue;
+        };
+        let name = project
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| project.display().to_string());
+        for v in installed
+            .iter()
+            .filter(|v| pin.matches_loose(v) && v.version_key() == newest.version_key())
+        {
+            pinned.push((v.folder_name(), name.clone()));
+        }
+    }
+
+    let running = process::running();
+    let mut per_minor: HashMap<(Option<String>, u32, u32, bool), usize> = HashMap::new();
+    let mut targets = Vec::new();
+    let mut reclaimed = 0;
+    for v in &installed {
+        let folder = v.folder_name();
+        // Linked engines belong to something else and don't count towards --keep
+        let seen = if v.label.is_some() {
+            0
+        } else {
+            let seen = per_minor
+                .entry((v.source.clone(), v.major, v.minor, v.mono))
+                .or_default();
+            *seen += 1;
+            *seen
+        };
+
+        let reason = if v.label.is_some() {
+            Some("linked".to_string())
+        } else if current.as_deref() == Some(folder.as_str()) {
+            Some("current".to_string())
+        } else if running.iter().any(|e| e.version == folder) {
+            Some("running".to_string())
+        } else if let Some((_, name)) = pinned.iter().find(|(f, _)| *f == folder) {
+            Some(format!("pinned by {name}"))
+        } else if seen <= keep {
+            Some(format!("newest {}.{}", v.major, v.minor))
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => println!(
+                "  {} {} {}",
+                "keep  ".dimmed(),
+                v.to_string().green(),
+                format!("({reason})").dimmed()
+            ),
+            None => {
+                let size = dir_size(&config.engine_dir.join(&folder));
+                reclaimed += size;
+                println!(
+                    "  {} {} {}",
+                    "remove".red(),
+                    v.to_string().red().bold(),
+                    HumanBytes(size).to_string().dimmed()
+                );
+                targets.push(v.clone());
+            }
+        }
+    }
+
+    if targets.is_empty() {
+        println!("{}", "Nothing to prune.".dimmed());
+        return Ok(());
+    }
+
+    if dry_run {
+        println!(
+            "  Would remove {} versions and reclaim {}",
+            targets.len(),
+            HumanBytes(reclaimed).to_string().bold()
+        );
+        return Ok(());
+    }
+
+    if !config
+        .prompter
+        .confirm(&format!("Remove {} versions?", targets.len()), "--yes")?
+    {
+        println!("{}", "Prune cancelled.".yellow());
+        return Ok(());
+    }
+
+    remove_versions(config, &targets)?;
+    println!(
+        "  Run {} to reclaim {}",
+        "godo trash empty".bold(),
+        HumanBytes(reclaimed).to_string().bold()
+    );
+
+    Ok(())
+}
+
+/// Coarse age such as `3d` or `5h`, for listings.
+fn format_age(secs: u64) -> String {
+    match secs {
+        s if s >= 86400 => format!("{}d", s / 86400),
+        s if s >= 3600 => format!("{}h", s / 3600),
+        s => format!("{}m", s / 60),
+    }
+}
+
+/// Total size of the files below `path`. Symlinks are not followed.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Refuses to touch installs that have an engine running, unless the user agrees on a
+/// terminal or passes `--yes`.
+fn ensure_not_running(config: &Config, targets: &[GodotVersion]) -> Result<()> {
+    for target in targets {
+        let running = process::running_version(target);
+        if running.is_empty() {
+            continue;
+        }
+        let pids: Vec<String> = running.iter().map(|e| e.pid.to_string()).collect();
+        let message = format!("{target} is running (PID {})", pids.join(", "));
+        let advice = format!("Close it or run 'godo kill {}' first", target.version_key());
+        if !config.prompter.is_interactive() && !config.prompter.assumes_yes() {
+            bail!("{message}. {advice}, or pass --yes to continue anyway");
+        }
+        if !config
+            .prompter
+            .confirm(&format!("{message}. Continue anyway?"), "--yes")?
+        {
+            bail!("{message}. {advice}");
+        }
+    }
+    Ok(())
+}
+
+pub fn ps() -> Result<()> {
+    let running = process::running();
+    let now = trash::now();
+    if running.is_empty() {
+        println!("{}", "No engines launched by godo are running.".dimmed());
+    }
+    for launch in &running {
+        println!(
+            "  {} {} {}",
+            format!("{:>7}", launch.pid).dimmed(),
+            launch_name(launch).green().bold(),
+            format!("up {}", format_age(now.saturating_sub(launch.started_at))).dimmed()
+        );
+    }
+
+    let finished = process::finished();
+    if finished.is_empty() {
+        return Ok(());
+    }
+    println!();
+    println!("  {}", "Recently exited".bold());
+    for launch in finished.iter().take(5) {
+        let Some(exit) = &launch.exit else {
+            continue;
+        };
+        let ago = format!("{} ago", format_age(now.saturating_sub(exit.at)));
+        if exit.crashed() {
+            println!(
+                "  {} {} {} {}",
+                format!("{:>7}", launch.pid).dimmed(),
+                launch_name(launch).red().bold(),
+                format!("crashed, {}", exit.describe()).red(),
+                ago.dimmed()
+            );
+            if let Some(log) = &launch.log {
+                println!("          {} {}", "log:".dimmed(), log.display());
+            }
+        } else {
+            println!(
+                "  {} {} {} {}",
+                format!("{:>7}", launch.pid).dimmed(),
+                launch_name(launch),
+                exit.describe().dimmed(),
+                ago.dimmed()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn launch_name(launch: &process::Launch) -> String {
+    launch
+        .version()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| launch.version.clone())
+}
+
+pub fn logs(version: Option<&str>, follow: bool) -> Result<()> {
+    let query = version
+        .map(|v| VersionQuery::from_input(v).context("Invalid version format"))
+        .transpose()?;
+
+    let mut logs = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(process::logs_dir()) {
+        for entry in entries.flatten() {
+            let folder = entry.file_name().to_string_lossy().to_string();
+            let matches = GodotVersion::from_folder(&folder)
+                .is_some_and(|v| query.as_ref().is_none_or(|q| q.matches_loose(&v)));
+            if matches {
+                logs.extend(process::logs_of(&folder));
+            }
+        }
+    }
+    let log = logs
+        .into_iter()
+        .max_by_key(|l| process::log_order(l))
+        .with_context(|| match version {
+            Some(v) => format!("No launch logs for '{v}'"),
+            None => "No launch logs yet".to_string(),
+        })?;
+
+    println!("{} {}", "==>".dimmed(), log.display().to_string().dimmed());
+    let mut file = std::fs::File::open(&log).context("Failed to open log")?;
+    let mut stdout = std::io::stdout();
+    std::io::copy(&mut file, &mut stdout)?;
+    if !follow {
+        return Ok(());
+    }
+
+    // Keep printing until the engine writing the log has exited
+    loop {
+        let alive = process::launch_of_log(&log).is_some_and(|l| l.is_alive());
+        std::io::copy(&mut file, &mut stdout)?;
+        stdout.flush()?;
+        if !alive {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+
+    Ok(())
+}
+
+pub fn kill(version: &str) -> Result<()> {
+    let query = VersionQuery::from_input(version).context("Invalid version format")?;
+    let matched: Vec<process::Launch> = process::running()
+        .into_iter()
+        .filter(|e| e.version().is_some_and(|v| query.matches_loose(&v)))
+        .collect();
+    if matched.is_empty() {
+        bail!("No running engine matches '{version}'");
+    }
+
+    for engine in &matched {
+        process::terminate(engine.pid)?;
+        println!(
+            "  {} Stopped {} (PID {})",
+            "✓".green(),
+            engine.version.green().bold(),
+            engine.pid
+        );
+    }
+
+    Ok(())
+}
+
+/// Moves the given installs to the trash. If `current` was among them, the user is asked
+/// whether to move it to the newest remaining install; without a terminal it is moved.
+fn remove_versions(config: &Config, targets: &[GodotVersion]) -> Result<()> {
+    let current_target = read_current_link(config);
+    let mut removed_current = None;
+
+    for target in targets {
+        let version_dir = config.engine_dir.join(target.folder_name());
+        if !version_dir.exists() {
+            bail!("Version directory not found: {}", version_dir.display());
+        }
+
+        let is_current = current_target.as_deref() == Some(target.folder_name().as_str());
+        trash::move_to_trash(config, target, is_current)?;
+        if is_current {
+            removed_current = Some(target);
+        }
+
+        println!(
+            "  {} Moved {} to the trash",
+            "✓".green(),
+            target.to_string().green().bold()
+        );
+    }
+
+    let Some(removed) = removed_current else {
+        return Ok(());
+    };
+
+    println!(
+        "  {} {} was the current version",
+        "!".yellow(),
+        removed.to_string().bold()
+    );
+    let remaining = get_installed_versions(config)?;
+    let next = remaining.iter().max();
+    let move_current = match next {
+        Some(next) if config.prompter.is_interactive() => config
+            .prompter
+            .confirm(&format!("Set {next} as current?"), "--yes")?,
+        Some(_) => true,
+        None => false,
+    };
+
+    match next {
+        Some(next) if move_current => {
+            update_current_symlink(config, &next.folder_name())?;
+            println!(
+                "  {} Current version changed from {} to {}",
+                "✓".green(),
+                removed,
+                next.to_string().green().bold()
+            );
+        }
+        _ => {
+            let current_link = config.current_link_path();
+            if current_link.symlink_metadata().is_ok() {
+                remove_symlink(&current_link)?;
+            }
+            println!(
+                "  {} No current version set. Run 'godo current <version>' or 'godo restore {}'",
+                "!".yellow(),
+                removed.version_key()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn restore(config: &Config, version: &str) -> Result<()> {
+    let query = VersionQuery::from_input(version).context("Invalid version format")?;
+    let entries = trash::entries(config)?;
+    // Entries are ordered newest removal first
+    let entry = entries
+        .iter()
+        .find(|e| e.version().is_some_and(|v| query.matches_loose(&v)))
+        .with_context(|| format!("No version matching '{version}' in the trash"))?;
+
+    trash::restore(config, entry)?;
+    let restored = entry.version().context("Failed to parse trashed version")?;
+    println!(
+        "  {} Restored {}",
+        "✓".green(),
+        restored.to_string().green().bold()
+    );
+
+    if entry.meta.was_current {
+        if read_current_link(config).is_none() {
+            update_current_symlink(config, &restored.folder_name())?;
+            println!(
+                "  {} Current version set to {}",
+                "✓".green(),
+                restored.to_string().green().bold()
+            );
+        } else {
+            println!(
+                "  {} It was the current version when removed. Run 'godo current {}' to switch back",
+                "!".yellow(),
+                restored.version_key()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn trash_list(config: &Config) -> Result<()> {
+    let entries = trash::entries(config)?;
+    if entries.is_empty() {
+        println!("{}", "The trash is empty.".dimmed());
+        return Ok(());
+    }
+
+    let now = trash::now();
+    let mut total = 0;
+    for entry in &entries {
+        let size = dir_size(&entry.path);
+        total += size;
+        println!(
+            "  {} {} {}",
+            entry.meta.version.bold(),
+            format!(
+                "removed {} ago",
+                format_age(now.saturating_sub(entry.meta.removed_at))
+            )
+            .dimmed(),
+            HumanBytes(size).to_string().dimmed()
+        );
+    }
+    println!("  {} in total", HumanBytes(total).to_string().bold());
+
+    Ok(())
+}
+
+pub fn trash_empty(config: &Config, older_than: Option<&str>) -> Result<()> {
+    let min_age = older_than.map(crate::config::parse_duration).transpose()?;
+    let now = trash::now();
+    let entries: Vec<trash::TrashEntry> = trash::entries(config)?
+        .into_iter()
+        .filter(|e| min_age.is_none_or(|age| now.saturating_sub(e.meta.removed_at) >= age))
+        .collect();
+
+    if entries.is_empty() {
+        println!("{}", "Nothing to delete.".dimmed());
+        return Ok(());
+    }
+
+    let names: Vec<&str> = entries.iter().map(|e| e.meta.version.as_str()).collect();
+    println!("  Will delete: {}", names.join(", ").red().bold());
+    if !config
+        .prompter
+        .confirm("Delete these versions permanently?", "--yes")?
+    {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    }
+
+    let mut freed = 0;
+    for entry in &entries {
+        freed += dir_size(&entry.path);
+        std::fs::remove_dir_all(&entry.path)
+            .with_context(|| format!("Failed to delete {}", entry.path.display()))?;
+    }
+    println!(
+        "  {} Freed {}",
+        "✓".green(),
+        HumanBytes(freed).to_string().green().bold()
+    );
+
+    Ok(())
+}
+
+pub fn sc(config: &Config, enable: bool, version: &str, mono: Option<bool>) -> Result<()> {
+    let Some((target, _)) = resolve_installed(config, version, mono, None, "Change")? else {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    };
+    let version_dir = config.engine_dir.join(target.folder_name());
+    // Godot looks for the marker next to the real executable, which godo does not own
+    if let Some(linked) = link::target(&version_dir) {
+        bail!(
+            "{target} is linked from {}. Manage its self-contained mode there",
+            linked.display()
+        );
+    }
+
+    if enable {
+        settings::set_self_contained(&version_dir, true)?;
+        println!(
+            "  {} {} keeps its editor settings in {}",
+            "✓".green(),
+            target.to_string().green().bold(),
+            version_dir.join("editor_data").display()
+        );
+        return Ok(());
+    }
+
+    // Ask before touching anything so a refused or failed prompt leaves the install as it was
+    let editor_data = version_dir.join("editor_data");
+    let has_data = std::fs::read_dir(&editor_data).is_ok_and(|mut d| d.next().is_some());
+    let delete = has_data
+        && config.prompter.confirm(
+            "Delete editor_data with the isolated settings of this install?",
+            "--yes",
+        )?;
+
+    settings::set_self_contained(&version_dir, false)?;
+    println!(
+        "  {} {} uses the shared editor settings",
+        "✓".green(),
+        target.to_string().green().bold()
+    );
+
+    if editor_data.exists() && !has_data {
+        std::fs::remove_dir(&editor_data).context("Failed to remove editor_data")?;
+    } else if delete {
+        std::fs::remove_dir_all(&editor_data).context("Failed to remove editor_data")?;
+        println!("  {} Deleted {}", "✓".green(), editor_data.display());
+    } else if has_data {
+        println!("  Kept {}", editor_data.display());
+    }
+
+    Ok(())
+}
+
+/// Resolves an install for `godo settings`, `None` when the picker was cancelled.
+fn settings_target(config: &Config, version: &str, action: &str) -> Result<Option<GodotVersion>> {
+    Ok(resolve_installed(config, version, None, None, action)?.map(|(v, _)| v))
+}
+
+pub fn settings_copy(config: &Config, from: &str, to: &str) -> Result<()> {
+    let Some(src) = settings_target(config, from, "Copy from")? else {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    };
+    let Some(dst) = settings_target(config, to, "Copy to")? else {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    };
+    if src.major != dst.major {
+        bail!(
+            "Editor settings of Godot {} and {} are not compatible",
+            src.major,
+            dst.major
+        );
+    }
+
+    let src_dir = settings::location(config, &src)?;
+    let dst_dir = settings::location(config, &dst)?;
+    let src_file = src_dir.join(settings::settings_file(&src));
+    let dst_file = dst_dir.join(settings::settings_file(&dst));
+    if src_file == dst_file {
+        bail!("{src} and {dst} already share {}", src_file.display());
+    }
+    if !src_file.exists() {
+        bail!("{src} has no editor settings at {}", src_file.display());
+    }
+
+    println!("  {} {}", "From".dimmed(), src_file.display());
+    println!("  {} {}", "To  ".dimmed(), dst_file.display());
+    let src_keys = settings::read_keys(&src_file)?;
+    let dst_keys = if dst_file.exists() {
+        settings::read_keys(&dst_file)?
+    } else {
+        Default::default()
+    };
+    let mut changes = 0;
+    for (key, value) in &src_keys {
+        match dst_keys.get(key) {
+            None => println!("  {} {key}", "+".green()),
+            Some(old) if old != value => println!("  {} {key}", "~".yellow()),
+            Some(_) => continue,
+        }
+        changes += 1;
+    }
+    for key in dst_keys.keys().filter(|k| !src_keys.contains_key(*k)) {
+        println!("  {} {key}", "-".red());
+        changes += 1;
+    }
+    if changes == 0 {
+        println!("  Settings are identical, copying templates and profiles only.");
+    }
+
+    if !config
+        .prompter
+        .confirm(&format!("Copy the settings of {src} to {dst}?"), "--yes")?
+    {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    }
+
+    if let Some(backup) = settings::backup(config, &dst)? {
+        println!(
+            "  {} Backed up the settings of {dst} as {}",
+            "✓".green(),
+            backup.id().bold()
+        );
+    }
+    settings::copy(&src, &src_dir, &dst, &dst_dir)?;
+    println!(
+        "  {} Copied editor settings to {}",
+        "✓".green(),
+        dst.to_string().green().bold()
+    );
+
+    Ok(())
+}
+
+pub fn settings_backup(config: &Config, version: &str) -> Result<()> {
+    let Some(target) = settings_target(config, version, "Back up")? else {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    };
+    match settings::backup(config, &target)? {
+        Some(backup) => println!(
+            "  {} Backed up the settings of {} as {}",
+            "✓".green(),
+            target.to_string().green().bold(),
+            backup.id().bold()
+        ),
+        None => bail!("{target} has no editor settings yet"),
+    }
+    Ok(())
+}
+
+pub fn settings_restore(config: &Config, version: &str, id: Option<&str>) -> Result<()> {
+    let query = VersionQuery::from_input(version).context("Invalid version format")?;
+    let backups = settings::backups()?;
+    // Backups are ordered newest first
+    let backup = backups
+        .iter()
+        .filter(|b| b.version().is_some_and(|v| query.matches_loose(&v)))
+        .find(|b| id.is_none_or(|id| b.id() == id))
+        .with_context(|| match id {
+            Some(id) => format!("No settings backup '{id}' for '{version}'"),
+            None => format!("No settings backup for '{version}'"),
+        })?;
+
+    println!(
+        "  Will restore {} to {}",
+        backup.id().bold(),
+        backup.meta.source.display()
+    );
+    if !config
+        .prompter
+        .confirm("Overwrite the current settings?", "--yes")?
+    {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    }
+
+    settings::restore(backup)?;
+    println!("  {} Restored editor settings", "✓".green());
+    Ok(())
+}
+
+pub fn settings_backups() -> Result<()> {
+    let backups = settings::backups()?;
+    if backups.is_empty() {
+        println!("{}", "No settings backups.".dimmed());
+        return Ok(());
+    }
+    let now = trash::now();
+    for backup in &backups {
+        println!(
+            "  {} {}",
+            backup.id().bold(),
+            format!(
+                "{} ago",
+                format_age(now.saturating_sub(backup.meta.created_at))
+            )
+            .dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Flags of `godo convert`
+#[derive(Debug, Clone, Copy)]
+pub struct ConvertOptions<'a> {
+    /// Godot 4 version to convert with, e.g. `4.x` or `4.3`
+    pub to: &'a str,
+    pub out: Option<&'a Path>,
+    pub in_place: bool,
+    /// Files larger than this are not converted, in KiB
+    pub max_file_kb: u64,
+    /// Lines longer than this are not converted
+    pub max_line_size: u64,
+}
+
+/// Folders of a Godot 3 project that are not copied for a conversion, the import cache
+/// is rebuilt by Godot 4
+const CONVERT_SKIP: [&str; 2] = [".import", ".godot"];
+
+/// Converts a Godot 3 project with `--convert-3to4` of a Godot 4 engine, on a copy unless
+/// `in_place` is set, and writes a report of what was converted.
+pub fn convert(config: &Config, input: &str, options: ConvertOptions) -> Result<()> {
+    let Some(project) = find_project(config, input)? else {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    };
+    if project.feature.is_some_and(|(major, _)| major != 3) {
+        bail!("{} is not a Godot 3 project", project.name);
+    }
+
+    let to = options.to.trim_end_matches(".x");
+    let query = VersionQuery::from_input(to).context("Invalid version format")?;
+    if query.major != 4 {
+        bail!("Projects can only be converted with Godot 4, e.g. --to 4.x or --to 4.3");
+    }
+    let query = VersionQuery {
+        mono: project.mono.then_some(true),
+        ..query
+    };
+
+    // Settle where the result goes before installing anything
+    let out = if options.in_place {
+        println!(
+            "  {} Converting in place rewrites {} for good",
+            "!".yellow(),
+            project.path.display()
+        );
+        if !config.prompter.confirm("Convert in place?", "--yes")? {
+            println!("{}", "Cancelled.".yellow());
+            return Ok(());
+        }
+        project.path.clone()
+    } else {
+        let out = match options.out {
+            Some(out) => out.to_path_buf(),
+            None => {
+                let name = project
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "project".to_string());
+                project.path.with_file_name(format!("{name}-godot4"))
+            }
+        };
+        if out.read_dir().is_ok_and(|mut d| d.next().is_some()) {
+            bail!(
+                "{} already exists and is not empty. Pick another directory with --out",
+                out.display()
+            );
+        }
+        // The copy would otherwise copy itself over and over
+        let project_dir = project
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| project.path.clone());
+        if resolve_path(&out).starts_with(&project_dir) {
+            bail!(
+                "{} is inside the project. Pick a directory outside it with --out",
+                out.display()
+            );
+        }
+        out
+    };
+
+    let engine = match engine_for(&get_installed_versions(config)?, &query) {
+        Some(engine) => engine.clone(),
+        None => {
+            let version = VersionQuery {
+                mono: None,
+                ..query.clone()
+            }
+            .to_string();
+            println!("  Godot {version} is not installed");
+            if !config.prompter.confirm("Install it?", "--yes")? {
+                println!("{}", "Cancelled.".yellow());
+                return Ok(());
+            }
+            install_one(
+                config,
+                &version,
+                InstallOptions {
+                    mono: Some(project.mono),
+                    silent: true,
+                    set_current: Some(AutoSetCurrent::Never),
+                    ..Default::default()
+                },
+            )?;
+            engine_for(&get_installed_versions(config)?, &query)
+                .cloned()
+                .context("The installed version does not match")?
+        }
+    };
+    let executable = find_godot_executable(&config.engine_dir.join(engine.folder_name()))?;
+
+    if !options.in_place {
+        println!("{} {}", "Copying to".dimmed(), out.display());
+        std::fs::create_dir_all(&out).context("Failed to create output directory")?;
+        for entry in std::fs::read_dir(&project.path)? {
+            let entry = entry?;
+            if CONVERT_SKIP.iter().any(|s| entry.file_name() == *s) {
+                continue;
+            }
+            let dest = out.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                trash::copy_dir(&entry.path(), &dest)?;
+            } else {
+                std::fs::copy(entry.path(), &dest)
+                    .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+            }
+        }
+        // A pin to Godot 3 would open the copy in the wrong engine
+        if project.pin.is_some() {
+            std::fs::write(
+                out.join(project::PIN_FILE),
+                format!("{}\n", engine.version_key()),
+            )
+            .context("Failed to update the version pin")?;
+        }
+    }
+
+    println!(
+        "{} {} {}",
+        "Converting with".dimmed(),
+        engine.to_string().green().bold(),
+        "(this can take a while)".dimmed()
+    );
+    let args: Vec<OsString> = vec![
+        "--headless".into(),
+        "--path".into(),
+        out.clone().into_os_string(),
+        "--convert-3to4".into(),
+        options.max_file_kb.to_string().into(),
+        options.max_line_size.to_string().into(),
+    ];
+    std::fs::create_dir_all(&config.temp_dir).context("Failed to create temp directory")?;
+    let output_path = config
+        .temp_dir
+        .join(format!("convert-{}.log", trash::now()));
+    let exit = process::capture(&executable, &out, &args, &output_path)?;
+    let output = std::fs::read_to_string(&output_path).unwrap_or_default();
+    let _ = std::fs::remove_file(&output_path);
+
+    let errors: Vec<&str> = output
+        .lines()
+        .filter(|l| l.trim_start().starts_with("ERROR"))
+        .collect();
+    let warnings: Vec<&str> = output
+        .lines()
+        .filter(|l| l.trim_start().starts_with("WARNING"))
+        .collect();
+    let summary = output
+        .lines()
+        .rev()
+        .find(|l| l.contains("Conversion ended"))
+        .map(str::trim);
+
+    let mut report = format!(
+        "Conversion of {} to Godot 4\n\nSource:  {}\nOutput:  {}\nEngine:  {}\nResult:  Godot {}\n",
+        project.name,
+        project.path.display(),
+        out.display(),
+        engine,
+        exit.describe()
+    );
+    if let Some(summary) = summary {
+        report.push_str(&format!("Summary: {summary}\n"));
+    }
+    for (title, lines) in [("Errors", &errors), ("Warnings", &warnings)] {
+        if !lines.is_empty() {
+            report.push_str(&format!("\n{title} ({})\n", lines.len()));
+            for line in lines.iter() {
+                report.push_str(&format!("  {}\n", line.trim()));
+            }
+        }
+    }
+    report.push_str("\nFull output\n\n");
+    report.push_str(&output);
+    let report_path = out.join("godo-convert-report.txt");
+    std::fs::write(&report_path, report).context("Failed to write the conversion report")?;
+
+    if let Some(summary) = summary {
+        println!("  {}", summary.dimmed());
+    }
+    if exit.crashed() {
+        eprintln!(
+            "  {} Godot {} during the conversion",
+            "!".red().bold(),
+            exit.describe()
+        );
+    } else {
+        println!(
+            "  {} {}",
+            "✓".green(),
+            format!("Converted {} into {}", project.name, out.display())
+                .green()
+                .bold()
+        );
+    }
+    println!(
+        "  {} errors, {} warnings. Report: {}",
+        errors.len(),
+        warnings.len(),
+        report_path.display()
+    );
+    if exit.crashed() {
+        bail!("The conversion failed");
+    }
+    Ok(())
+}
+
+/// Exports a project with the engine it needs, in the foreground. Returns the exit code
+/// of Godot.
+pub fn export(
+    config: &Config,
+    preset: &str,
+    output: &Path,
+    project: Option<&Path>,
+    debug: bool,
+) -> Result<i32> {
+    let dir = project.unwrap_or(Path::new("."));
+    let project = dir
+        .canonicalize()
+        .ok()
+        .and_then(|p| project::Project::read(&p))
+        .with_context(|| format!("No project.godot in {}", dir.display()))?;
+
+    let presets = export::preset_names(&project.path)?;
+    if !presets.iter().any(|p| p == preset) {
+        if presets.is_empty() {
+            bail!("{} has no export presets", project.name);
+        }
+        bail!(
+            "No export preset '{preset}' in {}. Presets: {}",
+            project.name,
+            presets.join(", ")
+        );
+    }
+
+    let engine = match project.required() {
+        Some(query) => engine_for(&get_installed_versions(config)?, &query)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "{} needs Godot {query}, which is not installed. Run 'godo install {query}'",
+                    project.name
+                )
+            })?,
+        None => {
+            let folder = read_current_link(config).context(
+                "Cannot tell which version the project needs and no current version is set",
+            )?;
+            GodotVersion::from_folder(&folder).context("Failed to parse current version")?
+        }
+    };
+    let executable = find_godot_executable(&config.engine_dir.join(engine.folder_name()))?;
+    ensure_templates(config, &engine)?;
+
+    // Godot resolves relative paths against the project
+    let output = std::env::current_dir()?.join(output);
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create output directory")?;
+    }
+    let (window, release) = if engine.major < 4 {
+        ("--no-window", "--export")
+    } else {
+        ("--headless", "--export-release")
+    };
+    let args: Vec<OsString> = vec![
+        window.into(),
+        "--path".into(),
+        project.path.clone().into_os_string(),
+        if debug { "--export-debug" } else { release }.into(),
+        preset.into(),
+        output.clone().into_os_string(),
+    ];
+
+    println!(
+        "{} {} {} {}",
+        "Exporting".dimmed(),
+        preset.bold(),
+        "with".dimmed(),
+        engine.to_string().green().bold()
+    );
+    let exit = process::exec(&executable, &project.path, &args)?;
+    match exit.code {
+        Some(0) => {
+            println!(
+                "  {} {}",
+                "✓".green(),
+                format!("Exported to {}", output.display()).green().bold()
+            );
+            Ok(0)
+        }
+        Some(code) => {
+            eprintln!("  {} Godot {}", "!".red().bold(), exit.describe());
+            Ok(code)
+        }
+        None => bail!("Godot {}", exit.describe()),
+    }
+}
+
+/// Downloads and installs the export templates of `engine` unless they are there already.
+fn ensure_templates(config: &Config, engine: &GodotVersion) -> Result<()> {
+    let dir = export::templates_dir(config, engine)?;
+    if export::has_templates(&dir) {
+        return Ok(());
+    }
+
+    println!("  Export templates for {engine} are not installed");
+    println!("{}", "Fetching releases...".dimmed());
+    let releases = github::fetch_releases_cached(config)?;
+    let version = GodotVersion {
+        mono: false,
+        label: None,
+        ..engine.clone()
+    };
+    let release = releases
+        .iter()
+        .find(|r| r.version().as_ref() == Some(&version))
+        .with_context(|| format!("No release {version} to download export templates from"))?;
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| export::is_templates_asset(&a.name, engine.mono))
+        .with_context(|| format!("Release {version} has no export templates"))?;
+
+    std::fs::create_dir_all(&config.temp_dir).context("Failed to create temp directory")?;
+    let checksums = fetch_checksums(config, &release.assets)?;
+    let archive = fetch_archive(config, asset, checksums.as_ref())?;
+    println!("{}", "Extracting...".dimmed());
+    extract_zip_strip_prefix(&archive, &dir)?;
+    if !config.keep_archives {
+        cleanup_temp(&config.temp_dir, &asset.name);
+    }
+    println!(
+        "  {} Installed export templates to {}",
+        "✓".green(),
+        dir.display()
+    );
+    Ok(())
+}
+
+/// Flags of `godo matrix`
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixOptions<'a> {
+    /// Versions to run, see `VersionSet`
+    pub versions: &'a str,
+    pub mono: bool,
+    /// Install the newest release of minor versions without an install
+    pub install: bool,
+    /// How many engines run at once
+    pub jobs: usize,
+    pub report: &'a Path,
+    /// Arguments passed to every engine
+    pub args: &'a [OsString],
+}
+
+/// Runs the engine headless once per minor version of a set, then prints a pass/fail table
+/// and writes a JUnit report. A non-zero exit code counts as a failure.
+pub fn matrix(config: &Config, options: MatrixOptions) -> Result<()> {
+    let set = VersionSet::parse(options.versions)?;
+    let minor_of = |v: &GodotVersion| (v.source.clone(), v.major, v.minor);
+
+    // The newest install of each minor version
+    let mut picked: BTreeMap<(Option<String>, u32, u32), GodotVersion> = BTreeMap::new();
+    for v in get_installed_versions(config)?
+        .into_iter()
+        .filter(|v| v.mono == options.mono && set.contains(v))
+    {
+        let entry = picked.entry(minor_of(&v)).or_insert_with(|| v.clone());
+        if v > *entry {
+            *entry = v;
+        }
+    }
+
+    let mut results = Vec::new();
+    if options.install {
+        println!("{}", "Fetching releases...".dimmed());
+        let releases = github::fetch_releases_cached(config)?;
+        let mut newest: BTreeMap<(Option<String>, u32, u32), GodotVersion> = BTreeMap::new();
+        for v in releases
+            .iter()
+            .filter_map(|r| r.version())
+            .filter(|v| config.default_channel.accepts(&v.pre) && set.contains(v))
+        {
+            let entry = newest.entry(minor_of(&v)).or_insert_with(|| v.clone());
+            if v > *entry {
+                *entry = v;
+            }
+        }
+        for (minor, release) in newest {
+            if picked.contains_key(&minor) {
+                continue;
+            }
+            let version = GodotVersion {
+                mono: options.mono,
+                ..release
+            };
+            println!("{} {}", "Installing".dimmed(), version.to_string().bold());
+            let installed = install_one(
+                config,
+                &version.version_key(),
+                InstallOptions {
+                    mono: Some(options.mono),
+                    silent: true,
+                    set_current: Some(AutoSetCurrent::Never),
+                    ..Default::default()
+                },
+            );
+            match installed {
+                Ok(()) => {
+                    picked.insert(minor, version);
+                }
+                Err(e) => {
+                    eprintln!("  {} {version}: {e}", "!".red().bold());
+                    results.push((version, Outcome::Error(format!("Install failed: {e}"))));
+                }
+            }
+        }
+    }
+
+    let targets: Vec<GodotVersion> = picked.into_values().collect();
+    if targets.is_empty() && results.is_empty() {
+        bail!(
+            "No installed versions match '{}'. Pass --install to install them",
+            options.versions
+        );
+    }
+
+    let jobs = options.jobs.clamp(1, targets.len().max(1));
+    println!(
+        "{} {} version{}{}",
+        "Running".dimmed(),
+        targets.len(),
+        if targets.len() == 1 { "" } else { "s" },
+        if jobs > 1 {
+            format!(", {jobs} at a time")
+        } else {
+            String::new()
+        }
+    );
+    std::fs::create_dir_all(&config.temp_dir).context("Failed to create temp directory")?;
+    let work_dir = std::env::current_dir()?;
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let outcomes: std::sync::Mutex<Vec<Option<Outcome>>> =
+        std::sync::Mutex::new(targets.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let Some(version) = targets.get(i) else {
+                    break;
+                };
+                let outcome = run_matrix_entry(config, version, &work_dir, options.args)
+                    .unwrap_or_else(|e| Outcome::Error(format!("{e:#}")));
+                let mark = if outcome.failed() {
+                    "✗".red()
+                } else {
+                    "✓".green()
+                };
+                println!("  {mark} {version}");
+                outcomes.lock().unwrap()[i] = Some(outcome);
+            });
+        }
+    });
+    let outcomes = outcomes.into_inner().unwrap();
+    results.extend(
+        targets
+            .into_iter()
+            .zip(outcomes)
+            .filter_map(|(v, o)| Some((v, o?))),
+    );
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+
+    println!();
+    let width = results
+        .iter()
+        .map(|(v, _)| v.to_string().len())
+        .max()
+        .unwrap_or(0);
+    for (version, outcome) in &results {
+        let version = format!("{:width$}", version.to_string());
+        match outcome {
+            Outcome::Ran { exit, duration, .. } if !exit.crashed() => println!(
+                "  {}  {}  {}",
+                version.bold(),
+                "pass".green().bold(),
+                format!("{:.1}s", duration.as_secs_f64()).dimmed()
+            ),
+            Outcome::Ran {
+                exit,
+                duration,
+                output,
+            } => {
+                println!(
+                    "  {}  {}  {} {}",
+                    version.bold(),
+                    "FAIL".red().bold(),
+                    exit.describe(),
+                    format!("{:.1}s", duration.as_secs_f64()).dimmed()
+                );
+                let lines: Vec<&str> = output.lines().collect();
+                for line in &lines[lines.len().saturating_sub(10)..] {
+                    println!("      {}", line.dimmed());
+                }
+            }
+            Outcome::Error(reason) => {
+                println!("  {}  {}  {reason}", version.bold(), "FAIL".red().bold())
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|(_, o)| o.failed()).count();
+    let passed = results.iter().filter(|(_, o)| o.passed()).count();
+    println!();
+    println!(
+        "  {} passed, {} failed",
+        passed.to_string().green().bold(),
+        failed.to_string().red().bold()
+    );
 
-pub fn rm(config: &Config, version: &str, mono: Option<bool>, silent: bool) -> Result<()> {
-    let query = VersionQuery::from_input(version).context("Invalid version format")?;
+    std::fs::write(options.report, matrix::junit(&results))
+        .with_context(|| format!("Failed to write {}", options.report.display()))?;
+    println!("  {} {}", "Report:".dimmed(), options.report.display());
 
-    let installed = get_installed_versions(config)?;
-    if installed.is_empty() {
-        bail!("No Godot versions installed");
+    if failed > 0 {
+        bail!("{failed} of {} versions failed", results.len());
     }
+    Ok(())
+}
 
-    let mono_flag_provided = mono.is_some();
-    let matched: Vec<GodotVersion> = installed
-        .iter()
-        .filter(|v| query.matches_loose(v))
-        .cloned()
-        .collect();
+/// Runs one engine of `godo matrix` and collects its output.
+fn run_matrix_entry(
+    config: &Config,
+    version: &GodotVersion,
+    work_dir: &Path,
+    args: &[OsString],
+) -> Result<Outcome> {
+    let executable = find_godot_executable(&config.engine_dir.join(version.folder_name()))?;
+    let mut full_args: Vec<OsString> = vec![if version.major < 4 {
+        "--no-window"
+    } else {
+        "--headless"
+    }
+    .into()];
+    full_args.extend(args.iter().cloned());
+
+    let output_path = config.temp_dir.join(format!(
+        "matrix-{}-{}.log",
+        std::process::id(),
+        version.folder_name()
+    ));
+    let started = std::time::Instant::now();
+    let exit = process::capture(&executable, work_dir, &full_args, &output_path)?;
+    let duration = started.elapsed();
+    let output = std::fs::read(&output_path)
+        .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+        .unwrap_or_default();
+    let _ = std::fs::remove_file(&output_path);
+    Ok(Outcome::Ran {
+        exit,
+        duration,
+        output,
+    })
+}
 
-    if matched.is_empty() {
-        bail!("No matching installed version found for '{version}'");
+/// `path` made absolute with symlinks resolved, also when its last components don't exist yet
+fn resolve_path(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(resolved) = current.canonicalize() {
+            return missing.iter().rev().fold(resolved, |p, name| p.join(name));
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                current = if parent.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    parent
+                };
+            }
+            _ => return path.to_path_buf(),
+        }
     }
+}
 
-    let target = if matched.len() == 1 {
-        &matched[0]
-    } else {
-        let latest = matched.iter().max().unwrap();
-        latest
-    };
+/// The project `input` names, or the one in the working directory
+fn snapshot_project(config: &Config, input: Option<&str>) -> Result<Option<PathBuf>> {
+    Ok(find_project(config, input.unwrap_or("."))?.map(|p| p.path))
+}
 
-    let mono = match mono {
-        Some(m) => m,
-        None => {
-            let has_mono = matched.iter().any(|v| v.mono);
-            let has_non_mono = matched.iter().any(|v| !v.mono);
-            if has_mono && has_non_mono {
-                ask_mono()?
-            } else {
-                target.mono
+pub fn snapshot_list(config: &Config, project: Option<&str>) -> Result<()> {
+    let path = match project {
+        Some(input) => match snapshot_project(config, Some(input))? {
+            Some(path) => Some(path),
+            None => {
+                println!("{}", "Cancelled.".yellow());
+                return Ok(());
             }
-        }
+        },
+        None => None,
     };
+    let snapshots: Vec<snapshot::Snapshot> = snapshot::snapshots()?
+        .into_iter()
+        .filter(|s| path.as_ref().is_none_or(|p| s.meta.project == *p))
+        .collect();
+    if snapshots.is_empty() {
+        println!("{}", "No project snapshots.".dimmed());
+        return Ok(());
+    }
+    let now = trash::now();
+    for snapshot in &snapshots {
+        println!(
+            "  {} {} {}",
+            snapshot.id().bold(),
+            format!(
+                "before opening in {}, {} ago",
+                snapshot.meta.engine,
+                format_age(now.saturating_sub(snapshot.meta.created_at))
+            )
+            .dimmed(),
+            snapshot.meta.project.display()
+        );
+    }
+    Ok(())
+}
 
-    let target = GodotVersion {
-        mono,
-        ..target.clone()
+pub fn snapshot_restore(config: &Config, project: Option<&str>, id: Option<&str>) -> Result<()> {
+    let Some(path) = snapshot_project(config, project)? else {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
     };
+    let snapshots = snapshot::snapshots()?;
+    // Snapshots are ordered newest first
+    let snapshot = snapshots
+        .iter()
+        .filter(|s| s.meta.project == path)
+        .find(|s| id.is_none_or(|id| s.id() == id))
+        .with_context(|| match id {
+            Some(id) => format!("No snapshot '{id}' of {}", path.display()),
+            None => format!("No snapshot of {}", path.display()),
+        })?;
 
-    let need_confirm = !silent || !mono_flag_provided;
-    if need_confirm {
-        println!("  Will remove: {}", format!("{target}").red().bold());
-        if !ask_yes_no("Continue?")? {
-            println!("{}", "Removal cancelled.".yellow());
-            return Ok(());
-        }
+    println!(
+        "  Will restore {} to {}",
+        snapshot.id().bold(),
+        path.display()
+    );
+    if !config
+        .prompter
+        .confirm("Overwrite project.godot and .godot?", "--yes")?
+    {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
     }
 
-    let version_dir = config.engine_dir.join(target.folder_name());
-    if !version_dir.exists() {
-        bail!("Version directory not found: {}", version_dir.display());
-    }
+    snapshot::restore(snapshot)?;
+    println!("  {} Restored project metadata", "✓".green());
+    Ok(())
+}
 
-    let current_target = read_current_link(config);
-    let is_current = current_target.as_deref() == Some(target.folder_name().as_str());
+/// `list --installed`: every install with its Mono, current and self-contained state.
+fn list_installed(config: &Config) -> Result<()> {
+    let mut installed = get_installed_versions(config)?;
+    if installed.is_empty() {
+        println!("No Godot versions installed.");
+        return Ok(());
+    }
+    installed.sort_by(|a, b| b.cmp(a).then(a.mono.cmp(&b.mono)));
+    let current_folder = read_current_link(config);
 
-    std::fs::remove_dir_all(&version_dir).context("Failed to remove version directory")?;
+    for ver in &installed {
+        let text = if ver.mono {
+            format!("{} {}", "●".cyan(), ver.to_string().cyan().bold())
+        } else {
+            format!("{} {}", "●".green(), ver.to_string().green().bold())
+        };
+        let text = if current_folder.as_deref() == Some(ver.folder_name().as_str()) {
+            text.underline()
+        } else {
+            text.normal()
+        };
+        let sc = if settings::is_self_contained(&config.engine_dir.join(ver.folder_name())) {
+            format!(" {}", "[sc]".magenta())
+        } else {
+            String::new()
+        };
+        let linked = link::target(&config.engine_dir.join(ver.folder_name()))
+            .map(|t| format!(" {}", format!("→ {}", t.display()).dimmed()))
+            .unwrap_or_default();
+        println!(" {text}{sc}{linked}");
+    }
 
+    println!();
     println!(
-        "  {} Removed {}",
-        "✓".green(),
-        target.to_string().green().bold()
+        " {} Std   {} Mono  {}  {} Self-contained",
+        "●".green(),
+        "●".cyan(),
+        "● Current".underline(),
+        "[sc]".magenta()
     );
-
-    if is_current {
-        println!(
-            "  {} Current version was pointing to the removed version.",
-            "!".yellow()
-        );
-        let remaining = get_installed_versions(config)?;
-        if let Some(latest) = remaining.iter().max() {
-            update_current_symlink(config, &latest.folder_name())?;
-            println!(
-                "  {} Current version updated to {}",
-                "✓".green(),
-                latest.to_string().green().bold()
-            );
-        } else {
-            let current_link = config.current_link_path();
-            if current_link.exists() || current_link.symlink_metadata().is_ok() {
-                remove_symlink(&current_link)?;
-            }
-            println!(
-                "  {} No versions installed, removed current link",
-                "!".yellow()
-            );
-        }
-    }
+    println!();
 
     Ok(())
 }
 
-pub fn list(config: &Config, beta: bool) -> Result<()> {
+pub fn list(config: &Config, beta: Option<bool>, installed_only: bool) -> Result<()> {
+    if installed_only {
+        return list_installed(config);
+    }
+
+    let beta = beta.unwrap_or(config.include_prereleases_in_list);
     println!("{}", "Fetching releases...".dimmed());
     let releases = match github::fetch_releases_cached(config) {
         Ok(r) => r,
         Err(e) => {
-            eprintln!("{} Failed to fetch releases: {e}", "!".yellow());
+            eprintln!("{} Failed to fetch releases: {e:#}", "!".yellow());
             Vec::new()
         }
     };
@@ -234,10 +1954,16 @@ pub fn list(config: &Config, beta: bool) -> Result<()> {
         return Ok(());
     }
 
-    let mut versions: Vec<GodotVersion> = releases
-        .iter()
-        .filter_map(|r| GodotVersion::from_tag(&r.tag_name))
-        .collect();
+    let mut versions: Vec<GodotVersion> = releases.iter().filter_map(|r| r.version()).collect();
+    versions.extend(
+        installed
+            .iter()
+            .filter(|v| v.label.is_some())
+            .map(|v| GodotVersion {
+                mono: false,
+                ..v.clone()
+            }),
+    );
     versions.sort();
     versions.dedup();
 
@@ -377,52 +2103,30 @@ pub fn list(config: &Config, beta: bool) -> Result<()> {
     Ok(())
 }
 
-pub fn current(config: &Config, version: &str, mono: Option<bool>, silent: bool) -> Result<()> {
-    let query = VersionQuery::from_input(version).context("Invalid version format")?;
-
-    let installed = get_installed_versions(config)?;
-    if installed.is_empty() {
-        bail!("No Godot versions installed");
-    }
-
-    let matched: Vec<GodotVersion> = installed
-        .iter()
-        .filter(|v| query.matches_loose(v))
-        .cloned()
-        .collect();
-
-    if matched.is_empty() {
-        bail!("No matching installed version found for '{version}'");
-    }
-
-    let target = matched.iter().max().unwrap();
-
-    let mono_flag_provided = mono.is_some();
-    let mono = match mono {
-        Some(m) => m,
-        None => {
-            let has_mono = matched.iter().any(|v| v.mono);
-            let has_non_mono = matched.iter().any(|v| !v.mono);
-            if has_mono && has_non_mono {
-                ask_mono()?
-            } else {
-                target.mono
-            }
-        }
-    };
-
-    let target = GodotVersion {
-        mono,
-        ..target.clone()
+pub fn current(
+    config: &Config,
+    version: &str,
+    mono: Option<bool>,
+    silent: bool,
+    latest: bool,
+) -> Result<()> {
+    let Some((target, picked)) = resolve_installed(config, version, mono, Some(latest), "Use")?
+    else {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
     };
 
+    let mono_flag_provided = picked || mono.is_some() || config.default_mono.choice().is_some();
     let need_confirm = !silent || !mono_flag_provided;
     if need_confirm {
         println!(
             "  Set current version to: {}",
             format!("{target}").green().bold()
         );
-        if !ask_yes_no("Continue?")? {
+        if !config
+            .prompter
+            .confirm("Continue?", "--yes (or --silent with --mono)")?
+        {
             println!("{}", "Cancelled.".yellow());
             return Ok(());
         }
@@ -438,40 +2142,16 @@ pub fn current(config: &Config, version: &str, mono: Option<bool>, silent: bool)
     Ok(())
 }
 
-pub fn run(config: &Config, version: Option<&str>, mono: Option<bool>) -> Result<()> {
+pub fn run(config: &Config, version: Option<&str>, options: RunOptions) -> Result<()> {
+    let log = options.log.unwrap_or(config.launch_logs);
     let target = if let Some(ver) = version {
-        let query = VersionQuery::from_input(ver).context("Invalid version format")?;
-        let installed = get_installed_versions(config)?;
-        if installed.is_empty() {
-            bail!("No Godot versions installed");
-        }
-
-        let matched: Vec<GodotVersion> = installed
-            .iter()
-            .filter(|v| query.matches_loose(v))
-            .cloned()
-            .collect();
-
-        if matched.is_empty() {
-            bail!("No matching installed version found for '{ver}'");
-        }
-
-        let target = matched.iter().max().unwrap().clone();
-
-        let mono = match mono {
-            Some(m) => m,
+        match resolve_installed(config, ver, options.mono, Some(options.latest), "Launch")? {
+            Some((target, _)) => target,
             None => {
-                let has_mono = matched.iter().any(|v| v.mono);
-                let has_non_mono = matched.iter().any(|v| !v.mono);
-                if has_mono && has_non_mono {
-                    ask_mono()?
-                } else {
-                    target.mono
-                }
+                println!("{}", "Cancelled.".yellow());
+                return Ok(());
             }
-        };
-
-        GodotVersion { mono, ..target }
+        }
     } else {
         let current_folder = read_current_link(config);
         let folder = current_folder
@@ -486,56 +2166,393 @@ pub fn run(config: &Config, version: Option<&str>, mono: Option<bool>) -> Result
 
     let executable = find_godot_executable(&version_dir)?;
 
+    let mut args = Vec::new();
+    let mut work_dir = version_dir;
+    if let Some(path) = options.project {
+        let project = path
+            .canonicalize()
+            .ok()
+            .and_then(|p| project::Project::read(&p))
+            .with_context(|| format!("No project.godot in {}", path.display()))?;
+        if !check_project(
+            config,
+            &project,
+            &target,
+            options.allow_upgrade,
+            options.snapshot,
+        )? {
+            println!("{}", "Cancelled.".yellow());
+            return Ok(());
+        }
+        args = editor_args(&project);
+        work_dir = project.path;
+    }
+
     println!(
         "{} {}",
         "Launching".dimmed(),
         target.to_string().green().bold()
     );
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::process::CommandExt;
-        std::process::Command::new(&executable)
-            .process_group(0)
-            .current_dir(&version_dir)
-            .spawn()
-            .context("Failed to launch Godot")?;
+    let log = process::launch(&target, &executable, &work_dir, &args, log)?;
+    if let Some(log) = log {
+        println!("  {} {}", "Logging to".dimmed(), log.display());
     }
 
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
-        const DETACHED_PROCESS: u32 = 0x00000008;
-        std::process::Command::new(&executable)
-            .creation_flags(CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS)
-            .current_dir(&version_dir)
-            .spawn()
-            .context("Failed to launch Godot")?;
+    Ok(())
+}
+
+/// Arguments that open `project` in the editor
+fn editor_args(project: &project::Project) -> Vec<OsString> {
+    vec![
+        OsString::from("--path"),
+        project.path.clone().into_os_string(),
+        OsString::from("-e"),
+    ]
+}
+
+/// Compares the version a project was saved with to the engine about to open it. Downgrades
+/// to an older major version are refused and other downgrades need confirming, as they can
+/// lose data. Upgrades rewrite the project and need `allow_upgrade`. Before either, a
+/// snapshot of the project metadata is offered. `false` means the user backed out.
+fn check_project(
+    config: &Config,
+    project: &project::Project,
+    engine: &GodotVersion,
+    allow_upgrade: bool,
+    snapshot: bool,
+) -> Result<bool> {
+    let Some((major, minor)) = project.feature else {
+        return Ok(true);
+    };
+    let saved = match minor {
+        Some(minor) => format!("{major}.{minor}"),
+        None => format!("{major}"),
+    };
+    // Godot 3 projects don't record their minor version
+    let engine_minor = minor.map(|_| engine.minor);
+    let name = project.name.bold();
+
+    match (engine.major, engine_minor).cmp(&(major, minor)) {
+        std::cmp::Ordering::Equal => return Ok(true),
+        std::cmp::Ordering::Less if engine.major < major => bail!(
+            "{name} was saved with Godot {saved} and cannot be opened in {engine}. Use a {saved} engine instead"
+        ),
+        std::cmp::Ordering::Less => {
+            println!(
+                "  {} {name} was saved with Godot {saved}. Opening it in the older {engine} can lose data",
+                "!".yellow()
+            );
+            if !config.prompter.confirm("Open it anyway?", "--yes")? {
+                return Ok(false);
+            }
+        }
+        std::cmp::Ordering::Greater if !allow_upgrade => bail!(
+            "{name} was saved with Godot {saved}. Opening it in {engine} upgrades it for good. Pass --allow-upgrade to do so"
+        ),
+        std::cmp::Ordering::Greater => println!(
+            "  {} Opening {name} in {engine} upgrades it from Godot {saved}",
+            "!".yellow()
+        ),
+    }
+
+    let take_snapshot = snapshot
+        || ((config.prompter.is_interactive() || config.prompter.assumes_yes())
+            && config
+                .prompter
+                .confirm("Snapshot project.godot and .godot first?", "--snapshot")?);
+    if take_snapshot {
+        let snapshot = snapshot::create(&project.path, engine)?;
+        println!(
+            "  {} Saved snapshot {}. Undo with {}",
+            "✓".green(),
+            snapshot.id().bold(),
+            "godo snapshot restore".bold()
+        );
+    }
+    Ok(true)
+}
+
+/// The newest install that can open a project needing `query`. Standard builds are preferred
+/// unless the project needs Mono.
+fn engine_for<'a>(installed: &'a [GodotVersion], query: &VersionQuery) -> Option<&'a GodotVersion> {
+    let matched = || installed.iter().filter(|v| query.matches_loose(v));
+    matched()
+        .filter(|v| !v.mono)
+        .max()
+        .or_else(|| matched().max())
+}
+
+pub fn projects(config: &Config) -> Result<()> {
+    let projects: Vec<project::Project> = project::known_projects()
+        .iter()
+        .filter_map(|p| project::Project::read(p))
+        .collect();
+    if projects.is_empty() {
+        println!("No projects found in the project list of the Godot editor.");
+        return Ok(());
+    }
+
+    let installed = get_installed_versions(config)?;
+    let name_width = projects.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let required: Vec<String> = projects
+        .iter()
+        .map(|p| match (p.required(), &p.pin) {
+            (Some(query), Some(_)) => format!("{query} (pinned)"),
+            (Some(query), None) => query.to_string(),
+            (None, _) => "?".to_string(),
+        })
+        .collect();
+    let required_width = required.iter().map(|r| r.len()).max().unwrap_or(0);
+
+    for (project, required) in projects.iter().zip(&required) {
+        let engine = match project.required() {
+            Some(query) => match engine_for(&installed, &query) {
+                Some(v) => format!("→ {v}").green().to_string(),
+                None => "not installed".red().to_string(),
+            },
+            None => "→ current".dimmed().to_string(),
+        };
+        println!(
+            "  {:name_width$}  {:required_width$}  {}",
+            project.name.bold(),
+            required,
+            engine
+        );
+        println!("  {}", project.path.display().to_string().dimmed());
+    }
+
+    Ok(())
+}
+
+/// Finds a project by its directory, or by its name in the project list of the editor.
+fn find_project(config: &Config, input: &str) -> Result<Option<project::Project>> {
+    let path = Path::new(input);
+    let path = if path.file_name().is_some_and(|n| n == "project.godot") {
+        path.parent().unwrap_or(path)
+    } else {
+        path
+    };
+    if path.join("project.godot").is_file() {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        return project::Project::read(&path)
+            .map(Some)
+            .context("Failed to read project.godot");
+    }
+
+    let mut matched: Vec<project::Project> = project::known_projects()
+        .iter()
+        .filter_map(|p| project::Project::read(p))
+        .filter(|p| {
+            let dir = p
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_lowercase());
+            p.name.to_lowercase() == input.to_lowercase()
+                || dir.is_some_and(|d| d == input.to_lowercase())
+        })
+        .collect();
+    match matched.len() {
+        0 => bail!(
+            "No project named '{input}' in the project list, and no project.godot at that path"
+        ),
+        1 => Ok(matched.pop()),
+        _ if config.prompter.is_interactive() => {
+            let labels: Vec<String> = matched
+                .iter()
+                .map(|p| format!("{} {}", p.name, p.path.display()))
+                .collect();
+            let picked = config
+                .prompter
+                .pick("Open which project?", &labels, &labels)?;
+            Ok(picked.map(|i| matched.swap_remove(i)))
+        }
+        _ => bail!("Several projects are named '{input}'. Pass the path of the project instead"),
+    }
+}
+
+/// Opens a project in the editor of the engine it needs.
+pub fn open(config: &Config, input: &str, allow_upgrade: bool, snapshot: bool) -> Result<()> {
+    let Some(project) = find_project(config, input)? else {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    };
+
+    let target = match project.required() {
+        Some(query) => {
+            let installed = get_installed_versions(config)?;
+            engine_for(&installed, &query).cloned().with_context(|| {
+                format!(
+                    "{} needs Godot {query}, which is not installed. Run 'godo install {query}'",
+                    project.name
+                )
+            })?
+        }
+        None => {
+            let folder = read_current_link(config).context(
+                "Cannot tell which version the project needs and no current version is set",
+            )?;
+            GodotVersion::from_folder(&folder).context("Failed to parse current version")?
+        }
+    };
+
+    if !check_project(config, &project, &target, allow_upgrade, snapshot)? {
+        println!("{}", "Cancelled.".yellow());
+        return Ok(());
+    }
+
+    let version_dir = config.engine_dir.join(target.folder_name());
+    let executable = find_godot_executable(&version_dir)?;
+    println!(
+        "{} {} {} {}",
+        "Opening".dimmed(),
+        project.name.bold(),
+        "with".dimmed(),
+        target.to_string().green().bold()
+    );
+
+    let log = process::launch(
+        &target,
+        &executable,
+        &project.path,
+        &editor_args(&project),
+        config.launch_logs,
+    )?;
+    if let Some(log) = log {
+        println!("  {} {}", "Logging to".dimmed(), log.display());
+    }
+    Ok(())
+}
+
+/// Finds the installed version `version` refers to. When several match, a terminal gets a
+/// picker and the newest one wins with `latest`; otherwise it is an error. `latest` is `None`
+/// for commands without a `--latest` flag. The flag returned tells whether the user picked
+/// it; `None` means the picker was cancelled.
+fn resolve_installed(
+    config: &Config,
+    version: &str,
+    mono: Option<bool>,
+    latest: Option<bool>,
+    action: &str,
+) -> Result<Option<(GodotVersion, bool)>> {
+    // Linked engines can be referred to by their label alone
+    let query = VersionQuery::from_input(version);
+    if query.is_none() && !is_valid_label(version) {
+        bail!("Invalid version format");
+    }
+
+    let installed = get_installed_versions(config)?;
+    if installed.is_empty() {
+        bail!("No Godot versions installed");
+    }
+
+    let mut matched: Vec<GodotVersion> = installed
+        .into_iter()
+        .filter(|v| match &query {
+            Some(query) => query.matches_loose(v),
+            None => v.label.as_deref() == Some(version),
+        })
+        .filter(|v| mono.is_none_or(|m| v.mono == m))
+        .collect();
+
+    if matched.is_empty() {
+        bail!("No matching installed version found for '{version}'");
+    }
+
+    let mut versions = distinct_versions(&matched);
+    if versions.len() > 1 && latest != Some(true) && !config.prompter.is_interactive() {
+        let names: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        bail!(
+            "'{version}' matches {}. Name one of them{}",
+            names.join(", "),
+            if latest.is_some() {
+                ", or pass --latest to take the newest"
+            } else {
+                ""
+            }
+        );
+    }
+
+    let mut picked = false;
+    if versions.len() > 1 && latest != Some(true) && config.prompter.is_interactive() {
+        let current = read_current_link(config);
+        let labels: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        let styled: Vec<String> = versions
+            .iter()
+            .map(|v| {
+                let builds: Vec<&GodotVersion> = matched
+                    .iter()
+                    .filter(|m| m.version_key() == v.version_key())
+                    .collect();
+                let text = if builds.iter().all(|b| b.mono) {
+                    format!("{} {}", "●".cyan(), v.to_string().cyan().bold())
+                } else {
+                    format!("{} {}", "●".green(), v.to_string().green().bold())
+                };
+                if builds
+                    .iter()
+                    .any(|b| current.as_deref() == Some(b.folder_name().as_str()))
+                {
+                    text.underline().to_string()
+                } else {
+                    text
+                }
+            })
+            .collect();
+        let Some(index) =
+            config
+                .prompter
+                .pick(&format!("{action} which version?"), &labels, &styled)?
+        else {
+            return Ok(None);
+        };
+        let chosen = versions.swap_remove(index).version_key();
+        matched.retain(|v| v.version_key() == chosen);
+        picked = true;
     }
 
-    #[cfg(not(any(unix, windows)))]
-    {
-        std::process::Command::new(&executable)
-            .current_dir(&version_dir)
-            .spawn()
-            .context("Failed to launch Godot")?;
-    }
+    let mono = match mono {
+        Some(m) => m,
+        None => {
+            let has_mono = matched.iter().any(|v| v.mono);
+            let has_non_mono = matched.iter().any(|v| !v.mono);
+            if has_mono && has_non_mono {
+                preferred_mono(config, action)?
+            } else {
+                matched[0].mono
+            }
+        }
+    };
 
-    Ok(())
+    let target = matched
+        .into_iter()
+        .filter(|v| v.mono == mono)
+        .max()
+        .unwrap();
+    Ok(Some((target, picked)))
 }
 
-fn ask_yes_no(prompt: &str) -> Result<bool> {
-    print!("  {prompt} [Y/n] ");
-    std::io::stdout().flush()?;
-    let mut input = String::new();
-    std::io::stdin().read_line(&mut input)?;
-    let answer = input.trim().to_lowercase();
-    Ok(answer.is_empty() || answer == "y" || answer == "yes")
+/// The versions among `matched`, newest first. Mono and non-Mono builds of one version
+/// count once: they are told apart by --mono or `default_mono`, not by the picker.
+fn distinct_versions(matched: &[GodotVersion]) -> Vec<GodotVersion> {
+    let mut versions: Vec<GodotVersion> = matched
+        .iter()
+        .map(|v| GodotVersion {
+            mono: false,
+            ..v.clone()
+        })
+        .collect();
+    versions.sort_by(|a, b| b.cmp(a));
+    versions.dedup();
+    versions
 }
 
-fn ask_mono() -> Result<bool> {
-    ask_yes_no("Install mono version?")
+/// Resolves an ambiguous mono choice from `default_mono`, asking only when it is `ask`.
+fn preferred_mono(config: &Config, action: &str) -> Result<bool> {
+    match config.default_mono.choice() {
+        Some(mono) => Ok(mono),
+        None => config.prompter.choose_mono(action),
+    }
 }
 
 pub fn get_installed_versions(config: &Config) -> Result<Vec<GodotVersion>> {
@@ -644,6 +2661,150 @@ fn remove_symlink(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Returns the archive for an asset, reusing a complete copy in `temp_dir` when available.
+/// Downloads go through the configured mirrors first and are verified against the
+/// official checksums, so a mirror never has to be trusted.
+fn fetch_archive(
+    config: &Config,
+    asset: &github::GithubAsset,
+    checksums: Option<&HashMap<String, String>>,
+) -> Result<PathBuf> {
+    // Assets of local directory sources are used in place
+    if let Some(path) = asset.browser_download_url.strip_prefix("file://") {
+        return Ok(PathBuf::from(path));
+    }
+
+    let dest = config.temp_dir.join(&asset.name);
+    let expected = checksums.and_then(|c| c.get(&asset.name));
+    // A partial download has the right name but not the size the release lists, a seeded or
+    // corrupted one is caught by the checksum when there is one
+    let cached_size = std::fs::metadata(&dest).map(|m| m.len()).ok();
+    if asset.size > 0 && cached_size == Some(asset.size) {
+        match expected {
+            Some(expected)
+                if !sha512_file(&dest).is_ok_and(|a| a.eq_ignore_ascii_case(expected)) =>
+            {
+                eprintln!(
+                    "  {} Checksum mismatch for the cached {}, downloading it again",
+                    "!".yellow(),
+                    asset.name
+                );
+                let _ = std::fs::remove_file(&dest);
+            }
+            _ => {
+                println!("  {} {} (cached)", "↓".dimmed(), asset.name);
+                return Ok(dest);
+            }
+        }
+    }
+
+    if config.offline {
+        let hint = if config.keep_archives {
+            ""
+        } else {
+            " Set keep_archives = true while online to keep downloaded archives for offline installs."
+        };
+        bail!(
+            "{} is not in the archive cache ({}) and downloads are disabled in offline mode. Use --from-file to install a local archive.{hint}",
+            asset.name,
+            config.temp_dir.display()
+        );
+    }
+
+    let urls = config.mirror_urls(&asset.browser_download_url);
+    let mut last_error = None;
+    for url in &urls {
+        let from_mirror = url != &asset.browser_download_url;
+        if from_mirror && expected.is_none() {
+            last_error = Some(anyhow!(
+                "Skipped mirror {url}: no official checksum to verify {} against",
+                asset.name
+            ));
+            continue;
+        }
+
+        if let Err(e) = download_with_progress(url, &dest, asset.size) {
+            eprintln!("  {} {e:#}", "!".yellow());
+            last_error = Some(e);
+            continue;
+        }
+
+        if let Some(expected) = expected {
+            let actual = sha512_file(&dest)?;
+            if !actual.eq_ignore_ascii_case(expected) {
+                let _ = std::fs::remove_file(&dest);
+                let e = anyhow!("Checksum mismatch for {} from {url}", asset.name);
+                eprintln!("  {} {e}", "!".yellow());
+                last_error = Some(e);
+                continue;
+            }
+        }
+        return Ok(dest);
+    }
+
+    Err(last_error.unwrap_or_else(|| anyhow!("No download URL for {}", asset.name)))
+}
+
+/// Downloads the official `SHA512-SUMS.txt` of a release, always from the original URL.
+/// `None` when running offline, when the release lists no `SHA512-SUMS.txt`, or when it is a
+/// local `file://` release. An error when the file is listed but downloading it fails.
+fn fetch_checksums(
+    config: &Config,
+    assets: &[github::GithubAsset],
+) -> Result<Option<HashMap<String, String>>> {
+    if config.offline {
+        return Ok(None);
+    }
+    let Some(sums) = assets.iter().find(|a| a.name == "SHA512-SUMS.txt") else {
+        return Ok(None);
+    };
+    if sums.browser_download_url.starts_with("file://") {
+        return Ok(None);
+    }
+
+    let content = crate::http::agent()
+        .get(&sums.browser_download_url)
+        .call()
+        .map_err(anyhow::Error::from)
+        .and_then(|mut r| {
+            if !r.status().is_success() {
+                bail!("server returned {}", r.status());
+            }
+            Ok(r.body_mut().read_to_string()?)
+        });
+    let content = content.context("Failed to fetch official checksums")?;
+    Ok(Some(
+        content
+            .lines()
+            .filter_map(|line| {
+                let (hash, name) = line.split_once(char::is_whitespace)?;
+                Some((
+                    name.trim().trim_start_matches('*').to_string(),
+                    hash.to_string(),
+                ))
+            })
+            .collect(),
+    ))
+}
+
+fn sha512_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path).context("Failed to open downloaded file")?;
+    let mut hasher = Sha512::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
 fn download_with_progress(url: &str, dest: &Path, expected_size: u64) -> Result<PathBuf> {
     println!(
         "  {} {}",
@@ -651,10 +2812,20 @@ fn download_with_progress(url: &str, dest: &Path, expected_size: u64) -> Result<
         url.split('/').next_back().unwrap_or("file")
     );
 
-    let mut response = ureq::agent()
+    // The body may take longer than the read timeout, only a stall fails it
+    let response = crate::http::agent()
         .get(url)
+        .config()
+        .timeout_recv_body(None)
+        .build()
         .call()
         .context("Failed to download file")?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to download file: server returned {}",
+            response.status()
+        );
+    }
 
     let total_size = if expected_size > 0 {
         expected_size
@@ -676,7 +2847,7 @@ fn download_with_progress(url: &str, dest: &Path, expected_size: u64) -> Result<
         .progress_chars("#>-"),
     );
 
-    let mut reader = response.body_mut().as_reader();
+    let mut reader = crate::http::stall_guard(response.into_body());
     let mut file = std::fs::File::create(dest).context("Failed to create temp file")?;
     let mut buf = [0u8; 8192];
     let mut downloaded: u64 = 0;
@@ -799,7 +2970,7 @@ fn rename_executables(dir: &Path) -> Result<()> {
     Ok(())
 }
 
-fn find_godot_executable(version_dir: &Path) -> Result<std::path::PathBuf> {
+pub fn find_godot_executable(version_dir: &Path) -> Result<std::path::PathBuf> {
     #[cfg(target_os = "windows")]
     let exe_name = "godot.exe";
     #[cfg(not(target_os = "windows"))]
@@ -841,9 +3012,532 @@ fn cleanup_temp(temp_dir: &Path, filename: &str) {
     }
 }
 
+/// Installs that have a newer stable patch in the same major.minor, paired with that patch.
+/// Only installs matching `query` are considered when it is given.
+fn outdated_installs(
+    config: &Config,
+    query: Option<&VersionQuery>,
+) -> Result<Vec<(GodotVersion, GodotVersion)>> {
+    println!("{}", "Fetching releases...".dimmed());
+    let releases = github::fetch_releases_cached(config)?;
+
+    let mut installed = get_installed_versions(config)?;
+    installed.sort_by(|a, b| b.cmp(a).then(a.mono.cmp(&b.mono)));
+
+    Ok(installed
+        .into_iter()
+        .filter(|v| v.label.is_none() && query.is_none_or(|q| q.matches_loose(v)))
+        .filter_map(|v| {
+            let newer = github::find_newer_patch(&releases, &v)?;
+            let newer = GodotVersion {
+                mono: v.mono,
+                ..newer
+            };
+            Some((v, newer))
+        })
+        .collect())
+}
+
+pub fn outdated(config: &Config) -> Result<()> {
+    let outdated = outdated_installs(config, None)?;
+    if outdated.is_empty() {
+        println!("  {} All installs are up to date", "✓".green());
+        return Ok(());
+    }
+
+    for (old, new) in &outdated {
+        let note = if config.engine_dir.join(new.folder_name()).exists() {
+            " (installed)".dimmed().to_string()
+        } else {
+            String::new()
+        };
+        println!(
+            "  {} {} → {}{note}",
+            "↑".yellow(),
+            old,
+            new.to_string().green().bold()
+        );
+    }
+    println!();
+    println!("  Run {} to install them", "godo upgrade".bold());
+
+    Ok(())
+}
+
+pub fn upgrade(config: &Config, version: Option<&str>, remove_old: bool) -> Result<()> {
+    let query = version
+        .map(|v| VersionQuery::from_input(v).context("Invalid version format"))
+        .transpose()?;
+    let outdated = outdated_installs(config, query.as_ref())?;
+    if outdated.is_empty() {
+        println!("  {} Everything is up to date", "✓".green());
+        return Ok(());
+    }
+
+    for (old, new) in &outdated {
+        println!("  {} → {}", old, new.to_string().green().bold());
+    }
+    if remove_old {
+        let old: Vec<GodotVersion> = outdated.iter().map(|(old, _)| old.clone()).collect();
+        ensure_not_running(config, &old)?;
+    }
+    let prompt = if remove_old {
+        "Upgrade and remove the old versions?"
+    } else {
+        "Upgrade these versions?"
+    };
+    if !config.prompter.confirm(prompt, "--yes")? {
+        println!("{}", "Upgrade cancelled.".yellow());
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for (old, new) in &outdated {
+        if let Err(e) = upgrade_one(config, old, new, remove_old) {
+            eprintln!("  {} {old}: {e}", "!".red().bold());
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        bail!("{failed} of {} upgrades failed", outdated.len());
+    }
+    Ok(())
+}
+
+fn upgrade_one(
+    config: &Config,
+    old: &GodotVersion,
+    new: &GodotVersion,
+    remove_old: bool,
+) -> Result<()> {
+    if !config.engine_dir.join(new.folder_name()).exists() {
+        println!("{} {}", "Installing".dimmed(), new.to_string().bold());
+        let self_contained =
+            settings::is_self_contained(&config.engine_dir.join(old.folder_name()));
+        install_one(
+            config,
+            &new.version_key(),
+            InstallOptions {
+                mono: Some(new.mono),
+                silent: true,
+                set_current: Some(AutoSetCurrent::Never),
+                self_contained,
+                ..Default::default()
+            },
+        )?;
+    }
+
+    if read_current_link(config).as_deref() == Some(old.folder_name().as_str()) {
+        update_current_symlink(config, &new.folder_name())?;
+        println!(
+            "  {} Current version moved to {}",
+            "✓".green(),
+            new.to_string().green().bold()
+        );
+    }
+
+    if remove_old {
+        remove_versions(config, std::slice::from_ref(old))?;
+    }
+
+    Ok(())
+}
+
 pub fn update(config: &Config) -> Result<()> {
+    config.ensure_online("update the release manifest")?;
     println!("{}", "Updating manifest from GitHub...".dimmed());
-    let releases = github::fetch_releases_remote(config.github_token.as_deref())?;
+    let previous = if Config::manifest_path().exists() {
+        github::load_manifest().unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let releases = github::fetch_releases_remote(config)?;
+    github::save_manifest(&releases)?;
     println!("  {} Fetched {} releases", "✓".green(), releases.len());
+
+    // Only report new tags when there was a previous manifest to compare against
+    if !previous.is_empty() {
+        let known: std::collections::HashSet<&str> =
+            previous.iter().map(|r| r.tag_name.as_str()).collect();
+        let mut new_versions: Vec<GodotVersion> = releases
+            .iter()
+            .filter(|r| !known.contains(r.tag_name.as_str()))
+            .filter_map(|r| r.version())
+            .collect();
+        new_versions.sort_by(|a, b| b.cmp(a));
+
+        let (stable, pre): (Vec<GodotVersion>, Vec<GodotVersion>) =
+            new_versions.into_iter().partition(|v| v.is_stable());
+        if stable.is_empty() && pre.is_empty() {
+            println!("  No new releases since the last update.");
+        }
+        for v in &stable {
+            println!(
+                "  {} New stable: {}",
+                "+".green(),
+                v.to_string().green().bold()
+            );
+        }
+        for v in &pre {
+            println!("  {} New prerelease: {}", "+".cyan(), v.to_string().cyan());
+        }
+    }
+
+    let installed = get_installed_versions(config)?;
+    let mut reported = std::collections::HashSet::new();
+    for ver in &installed {
+        if !reported.insert(ver.version_key()) {
+            continue;
+        }
+        if let Some(newer) = github::find_newer_patch(&releases, ver) {
+            println!(
+                "  {} {} can be upgraded to {}",
+                "↑".yellow(),
+                ver.version_key(),
+                newer.to_string().green().bold()
+            );
+        }
+    }
+
+    print_rate_limit(config);
+    Ok(())
+}
+
+pub fn doctor(config: &Config, network: bool) -> Result<()> {
+    println!("  Config file:  {}", Config::config_path().display());
+    println!("  Manifest at:  {}", Config::manifest_path().display());
+    println!("  Engine dir:   {}", config.engine_dir.display());
+    println!("  Temp dir:     {}", config.temp_dir.display());
+
+    let manifest_path = Config::manifest_path();
+    let manifest_age = std::fs::metadata(&manifest_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.elapsed().ok());
+    match manifest_age {
+        Some(age) => {
+            let stale = age.as_secs() >= config.invalidate_time;
+            let age = format!("{}m old", age.as_secs() / 60);
+            println!(
+                "  Manifest:     {}",
+                if stale { age.yellow() } else { age.normal() }
+            );
+        }
+        None => println!("  Manifest:     {}", "not cached".yellow()),
+    }
+
+    let installed = get_installed_versions(config)?;
+    println!("  Installed:    {} version(s)", installed.len());
+    match read_current_link(config) {
+        Some(current) => println!("  Current:      {}", current.green()),
+        None => println!("  Current:      {}", "not set".yellow()),
+    }
+
+    if config.github_token.is_some() {
+        println!("  GitHub token: {}", "set".green());
+    } else {
+        println!("  GitHub token: {}", "not set".yellow());
+    }
+    print_rate_limit(config);
+
+    if network {
+        println!();
+        probe_network(config);
+    }
+
+    Ok(())
+}
+
+/// Tests connectivity to GitHub and every configured mirror through the shared agent.
+fn probe_network(config: &Config) {
+    for (label, value) in crate::http::describe(&config.network) {
+        println!("  {:<13} {value}", format!("{label}:"));
+    }
+    if config.offline {
+        println!("  {}", "Connectivity checks skipped (offline)".dimmed());
+        return;
+    }
+
+    let mut targets = vec![
+        "https://api.github.com".to_string(),
+        "https://github.com".to_string(),
+    ];
+    targets.extend(config.mirrors.iter().map(|m| m.replace.clone()));
+
+    for url in targets {
+        let started = std::time::Instant::now();
+        match crate::http::agent().head(&url).call() {
+            Ok(response) => println!(
+                "  {} {url} ({}, {}ms)",
+                "✓".green(),
+                response.status(),
+                started.elapsed().as_millis()
+            ),
+            Err(e) => println!("  {} {url}: {}", "✗".red(), e.to_string().red()),
+        }
+    }
+}
+
+fn print_rate_limit(config: &Config) {
+    if config.offline {
+        println!("  GitHub quota: {}", "skipped (offline)".dimmed());
+        return;
+    }
+    match github::fetch_rate_limit(config.github_token.as_deref()) {
+        Ok(limit) => println!("  GitHub quota: {}", limit.describe()),
+        Err(e) => println!("  GitHub quota: {}", e.to_string().yellow()),
+    }
+}
+
+pub fn config_list() -> Result<()> {
+    let config = Config::read_file()?;
+    let values = toml::Table::try_from(&config).context("Failed to serialize config")?;
+    for (key, kind, env) in crate::config::KEYS {
+        let value = lookup_key(&values, key)
+            .map(|v| display_value(*kind, v))
+            .unwrap_or_else(|| "(not set)".dimmed().to_string());
+        println!("  {key} = {value}{}", env_note(env));
+    }
+    if !config.sources.is_empty() {
+        println!(
+            "  sources = {} entries (edit the file to change)",
+            config.sources.len()
+        );
+    }
+    if !config.mirrors.is_empty() {
+        println!(
+            "  mirrors = {} entries (edit the file to change)",
+            config.mirrors.len()
+        );
+    }
+    Ok(())
+}
+
+pub fn config_get(key: &str) -> Result<()> {
+    let (kind, env) = crate::config::key_info(key)?;
+    let config = Config::read_file()?;
+    let values = toml::Table::try_from(&config).context("Failed to serialize config")?;
+    match lookup_key(&values, key) {
+        Some(value) => println!("{}{}", display_value(kind, value), env_note(env)),
+        None => println!("{}{}", "(not set)".dimmed(), env_note(env)),
+    }
+    Ok(())
+}
+
+pub fn config_set(key: &str, value: &str) -> Result<()> {
+    let (kind, _) = crate::config::key_info(key)?;
+    let value = crate::config::parse_key_value(kind, value)?;
+    let mut table = Config::read_table()?;
+
+    let (parent, leaf) = match key.split_once('.') {
+        Some((section, leaf)) => {
+            let section = table
+                .entry(section)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .with_context(|| format!("'{section}' in the config file is not a table"))?;
+            (section, leaf)
+        }
+        None => (&mut table, key),
+    };
+    let shown = display_value(kind, &value);
+    parent.insert(leaf.to_string(), value);
+
+    Config::write_table(&table)?;
+    println!("  {} {key} = {shown}", "✓".green());
+    Ok(())
+}
+
+pub fn config_unset(key: &str) -> Result<()> {
+    crate::config::key_info(key)?;
+    let mut table = Config::read_table()?;
+
+    match key.split_once('.') {
+        Some((section, leaf)) => {
+            if let Some(section) = table.get_mut(section).and_then(|v| v.as_table_mut()) {
+                section.remove(leaf);
+            }
+            if table
+                .get(section)
+                .and_then(|v| v.as_table())
+                .is_some_and(|t| t.is_empty())
+            {
+                table.remove(section);
+            }
+        }
+        None => {
+            table.remove(key);
+        }
+    }
+
+    // Required keys fall back to their defaults instead of becoming invalid
+    let defaults =
+        toml::Table::try_from(Config::default_config()).context("Failed to serialize config")?;
+    for required in ["engine_dir", "temp_dir"] {
+        if !table.contains_key(required) {
+            if let Some(value) = defaults.get(required) {
+                table.insert(required.to_string(), value.clone());
+            }
+        }
+    }
+
+    Config::write_table(&table)?;
+    println!("  {} Unset {key}", "✓".green());
+    Ok(())
+}
+
+pub fn config_edit() -> Result<()> {
+    let path = Config::config_path();
+    if !path.exists() {
+        std::fs::create_dir_all(Config::config_dir())
+            .context("Failed to create config directory")?;
+        Config::default_config().save()?;
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| {
+            if cfg!(target_os = "windows") {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().context("Editor command is empty")?;
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{editor}'"))?;
+    if !status.success() {
+        bail!("Editor exited with {status}");
+    }
+
+    match Config::read_file() {
+        Ok(_) => println!("  {} Config is valid", "✓".green()),
+        Err(e) => {
+            println!("  {} {e:#}", "!".yellow());
+            println!("  Run 'godo config edit' again to fix it.");
+        }
+    }
+    Ok(())
+}
+
+pub fn config_path() -> Result<()> {
+    println!("{}", Config::config_path().display());
     Ok(())
 }
+
+fn lookup_key<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    match key.split_once('.') {
+        Some((section, leaf)) => table.get(section)?.as_table()?.get(leaf),
+        None => table.get(key),
+    }
+}
+
+fn display_value(kind: KeyKind, value: &toml::Value) -> String {
+    match (kind, value) {
+        (KeyKind::Secret, toml::Value::String(secret)) => mask_secret(secret),
+        (_, toml::Value::String(text)) => text.clone(),
+        (_, other) => other.to_string(),
+    }
+}
+
+fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}{tail}", "*".repeat(chars.len() - 4))
+}
+
+fn env_note(env: &[&str]) -> String {
+    env.iter()
+        .find(|name| std::env::var(name).is_ok_and(|v| !v.is_empty()))
+        .map(|name| format!(" {}", format!("(overridden by {name})").yellow()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MonoPreference;
+    use crate::prompt::Prompter;
+    use crate::testing::TempDir;
+
+    fn version(folder: &str) -> GodotVersion {
+        GodotVersion::from_folder(folder).unwrap()
+    }
+
+    /// A config without a terminal whose engine directory holds `installed`
+    fn config_with(dir: &TempDir, installed: &[&str], default_mono: MonoPreference) -> Config {
+        for folder in installed {
+            std::fs::create_dir_all(dir.path().join(folder)).unwrap();
+        }
+        Config {
+            engine_dir: dir.path().to_path_buf(),
+            default_mono,
+            prompter: Prompter::new(false, true),
+            ..Config::default_config()
+        }
+    }
+
+    #[test]
+    fn both_builds_of_a_version_count_once() {
+        let matched = [
+            version("4.2.2-stable"),
+            version("4.3.0-stable-mono"),
+            version("4.3.0-stable"),
+        ];
+        assert_eq!(distinct_versions(&matched[1..]), [version("4.3.0-stable")]);
+        assert_eq!(
+            distinct_versions(&matched),
+            [version("4.3.0-stable"), version("4.2.2-stable")]
+        );
+    }
+
+    #[test]
+    fn both_builds_of_one_version_follow_default_mono() {
+        let installed = ["4.3.0-stable", "4.3.0-stable-mono"];
+        for (preference, mono) in [
+            (MonoPreference::Mono, true),
+            (MonoPreference::Standard, false),
+        ] {
+            let dir = TempDir::new();
+            let config = config_with(&dir, &installed, preference);
+            let (target, picked) = resolve_installed(&config, "4.3", None, Some(false), "Use")
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                target,
+                GodotVersion {
+                    mono,
+                    ..version("4.3.0-stable")
+                }
+            );
+            assert!(!picked);
+        }
+    }
+
+    #[test]
+    fn several_versions_fail_without_a_terminal() {
+        let dir = TempDir::new();
+        let config = config_with(
+            &dir,
+            &["4.2.2-stable", "4.3.0-stable", "4.3.0-stable-mono"],
+            MonoPreference::Standard,
+        );
+        let error = resolve_installed(&config, "4", None, Some(false), "Use").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'4' matches 4.3.0-stable, 4.2.2-stable. Name one of them, or pass --latest to take the newest"
+        );
+        let (target, _) = resolve_installed(&config, "4", None, Some(true), "Use")
+            .unwrap()
+            .unwrap();
+        assert_eq!(target, version("4.3.0-stable"));
+    }
+}
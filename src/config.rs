@@ -1,84 +1,603 @@
-use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::prompt::Prompter;
+use crate::version::Channel;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Config {
     pub engine_dir: PathBuf,
     pub temp_dir: PathBuf,
-    /// Cache invalidation time in seconds. Default: 10800 (3 hours)
-    #[serde(default = "default_invalidate_time")]
+    /// Cache invalidation time in seconds, or a duration like `3h`. Default: 10800 (3 hours)
+    #[serde(
+        default = "default_invalidate_time",
+        deserialize_with = "deserialize_duration"
+    )]
     pub invalidate_time: u64,
     /// GitHub personal access token for API authentication. Optional but recommended to avoid rate limits.
     #[serde(default)]
     pub github_token: Option<String>,
+    /// Keep downloaded archives in `temp_dir` so they can be reinstalled offline. Off by
+    /// default, so offline installs only find archives downloaded after turning it on.
+    #[serde(default)]
+    pub keep_archives: bool,
+    /// Whether to pick Mono builds without asking: `true`, `false` or `"ask"`
+    #[serde(default)]
+    pub default_mono: MonoPreference,
+    /// Whether `install` makes the new version current: always, never, ask or if-newer
+    #[serde(default)]
+    pub auto_set_current: AutoSetCurrent,
+    /// Least stable release `install` picks when no pre-release is given: stable, rc or beta
+    #[serde(default)]
+    pub default_channel: Channel,
+    /// Whether `list` shows pre-releases without `--beta`
+    #[serde(default)]
+    pub include_prereleases_in_list: bool,
+    /// Whether `run` writes the output of engines to a log, overridden by `--log` / `--no-log`
+    #[serde(default = "default_launch_logs")]
+    pub launch_logs: bool,
+    /// Additional release sources. Their versions are namespaced by the source name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SourceConfig>,
+    /// Download mirrors, tried in order before the original URL
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<MirrorConfig>,
+    /// Proxy, certificate and timeout settings for all HTTP requests
+    #[serde(default, skip_serializing_if = "NetworkConfig::is_empty")]
+    pub network: NetworkConfig,
+    /// Set from `--offline` / `GODO_OFFLINE` at runtime, never persisted.
+    #[serde(skip)]
+    pub offline: bool,
+    /// Built from `--yes` / `--no-input` and TTY detection at runtime, never persisted.
+    #[serde(skip)]
+    pub prompter: Prompter,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MonoPreference {
+    Mono,
+    Standard,
+    #[default]
+    Ask,
+}
+
+impl MonoPreference {
+    /// The preferred choice, `None` when the user should be asked.
+    pub fn choice(&self) -> Option<bool> {
+        match self {
+            MonoPreference::Mono => Some(true),
+            MonoPreference::Standard => Some(false),
+            MonoPreference::Ask => None,
+        }
+    }
+}
+
+impl Serialize for MonoPreference {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        match self.choice() {
+            Some(mono) => s.serialize_bool(mono),
+            None => s.serialize_str("ask"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MonoPreference {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bool(bool),
+            Text(String),
+        }
+        match Raw::deserialize(d)? {
+            Raw::Bool(true) => Ok(MonoPreference::Mono),
+            Raw::Bool(false) => Ok(MonoPreference::Standard),
+            Raw::Text(text) => match text.as_str() {
+                "true" => Ok(MonoPreference::Mono),
+                "false" => Ok(MonoPreference::Standard),
+                "ask" => Ok(MonoPreference::Ask),
+                _ => Err(serde::de::Error::custom(format!(
+                    "invalid default_mono '{text}', expected true, false or \"ask\""
+                ))),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutoSetCurrent {
+    Always,
+    Never,
+    #[default]
+    Ask,
+    /// Only when the new version is newer than the current one
+    IfNewer,
+}
+
+/// A `[[sources]]` entry in `config.toml`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SourceConfig {
+    /// Namespace of the versions, e.g. `studio` for `studio/4.3.1-patch2`
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: SourceKind,
+    /// Access token sent to GitHub and Gitea APIs
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Asset name pattern, supports `*` and the `{os}`, `{arch}` and `{mono}` placeholders
+    #[serde(default)]
+    pub asset_pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SourceKind {
+    /// GitHub or GitHub Enterprise, `api_url` defaults to `https://api.github.com`
+    Github {
+        repo: String,
+        #[serde(default)]
+        api_url: Option<String>,
+    },
+    /// Gitea or Forgejo instance
+    Gitea { url: String, repo: String },
+    /// Static JSON index in the GitHub releases format
+    Json { url: String },
+    /// Local directory with one sub-directory of archives per release tag
+    Local { path: PathBuf },
+}
+
+/// A `[[mirrors]]` entry in `config.toml`. Asset URLs starting with `pattern`
+/// have that prefix replaced by `replace`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MirrorConfig {
+    pub pattern: String,
+    pub replace: String,
+}
+
+/// The `[network]` table in `config.toml`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct NetworkConfig {
+    /// Proxy URL, overrides `HTTPS_PROXY`/`HTTP_PROXY`/`ALL_PROXY`. Empty disables proxying.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Comma separated hosts that bypass the proxy, overrides `NO_PROXY`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+    /// PEM bundle with root certificates, e.g. of a TLS intercepting proxy. Replaces the
+    /// built-in roots, only the bundle and the system store of Unix systems are trusted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+    /// Seconds to wait for a connection. Default: 30
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+    /// Seconds to wait for the server to respond or a download to send more data. Default: 60
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_timeout: Option<u64>,
+}
+
+impl NetworkConfig {
+    pub fn is_empty(&self) -> bool {
+        self.proxy.is_none()
+            && self.no_proxy.is_none()
+            && self.ca_bundle.is_none()
+            && self.connect_timeout.is_none()
+            && self.read_timeout.is_none()
+    }
 }
 
 fn default_invalidate_time() -> u64 {
     10800 // 3 hours
 }
 
+fn default_launch_logs() -> bool {
+    true
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(d: D) -> std::result::Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Seconds(u64),
+        Text(String),
+    }
+    match Raw::deserialize(d)? {
+        Raw::Seconds(secs) => Ok(secs),
+        Raw::Text(text) => parse_duration(&text).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Parses durations like `90`, `45s`, `15m`, `3h`, `1d`, `1w` or `1h30m` into seconds.
+pub fn parse_duration(input: &str) -> Result<u64> {
+    let input = input.trim();
+    if let Ok(secs) = input.parse() {
+        return Ok(secs);
+    }
+    if input.is_empty() {
+        bail!("Invalid duration '', expected e.g. 90, 15m, 3h or 1d");
+    }
+    if input.bytes().all(|b| b.is_ascii_digit()) {
+        bail!("Duration '{input}' is too long");
+    }
+
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 604800,
+            _ => bail!("Invalid duration '{input}', expected e.g. 90, 15m, 3h or 1d"),
+        };
+        let value: u64 = number
+            .parse()
+            .with_context(|| format!("Invalid duration '{input}'"))?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .with_context(|| format!("Duration '{input}' is too long"))?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        bail!("Invalid duration '{input}', the last number has no unit");
+    }
+    Ok(total)
+}
+
+/// How the value of a settable key is parsed and displayed by `godo config`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyKind {
+    Path,
+    File,
+    Duration,
+    Seconds,
+    Bool,
+    Text,
+    Secret,
+    Choice(&'static [&'static str]),
+}
+
+/// Keys supported by `godo config`, with their kind and overriding environment variable.
+pub const KEYS: &[(&str, KeyKind, &[&str])] = &[
+    ("engine_dir", KeyKind::Path, &["GODO_ENGINE_DIR"]),
+    ("temp_dir", KeyKind::Path, &["GODO_TEMP_DIR"]),
+    ("invalidate_time", KeyKind::Duration, &[]),
+    (
+        "github_token",
+        KeyKind::Secret,
+        &["GITHUB_TOKEN", "GH_TOKEN"],
+    ),
+    ("keep_archives", KeyKind::Bool, &[]),
+    (
+        "default_mono",
+        KeyKind::Choice(&["true", "false", "ask"]),
+        &[],
+    ),
+    (
+        "auto_set_current",
+        KeyKind::Choice(&["always", "never", "ask", "if-newer"]),
+        &[],
+    ),
+    (
+        "default_channel",
+        KeyKind::Choice(&["stable", "rc", "beta"]),
+        &[],
+    ),
+    ("include_prereleases_in_list", KeyKind::Bool, &[]),
+    ("launch_logs", KeyKind::Bool, &[]),
+    ("network.proxy", KeyKind::Text, &[]),
+    ("network.no_proxy", KeyKind::Text, &[]),
+    ("network.ca_bundle", KeyKind::File, &[]),
+    ("network.connect_timeout", KeyKind::Seconds, &[]),
+    ("network.read_timeout", KeyKind::Seconds, &[]),
+];
+
+pub fn key_info(key: &str) -> Result<(KeyKind, &'static [&'static str])> {
+    KEYS.iter()
+        .find(|(name, _, _)| *name == key)
+        .map(|(_, kind, env)| (*kind, *env))
+        .with_context(|| {
+            let names: Vec<&str> = KEYS.iter().map(|(name, _, _)| *name).collect();
+            format!("Unknown key '{key}'. Available keys: {}", names.join(", "))
+        })
+}
+
+/// Validates a value typed by the user and converts it to its TOML representation.
+pub fn parse_key_value(kind: KeyKind, raw: &str) -> Result<toml::Value> {
+    let value = match kind {
+        KeyKind::Path | KeyKind::File => {
+            let path = expand_home(raw);
+            if !path.is_absolute() {
+                bail!("'{raw}' is not an absolute path");
+            }
+            if kind == KeyKind::Path && path.exists() && !path.is_dir() {
+                bail!("'{}' exists but is not a directory", path.display());
+            }
+            if kind == KeyKind::File && !path.is_file() {
+                bail!("'{}' is not a file", path.display());
+            }
+            toml::Value::String(path.to_string_lossy().to_string())
+        }
+        KeyKind::Duration => toml::Value::Integer(
+            i64::try_from(parse_duration(raw)?)
+                .ok()
+                .with_context(|| format!("Duration '{raw}' is too long"))?,
+        ),
+        KeyKind::Seconds => {
+            let secs: u32 = raw
+                .parse()
+                .with_context(|| format!("'{raw}' is not a number of seconds"))?;
+            toml::Value::Integer(secs.into())
+        }
+        KeyKind::Bool => match raw.to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => toml::Value::Boolean(true),
+            "false" | "no" | "off" | "0" => toml::Value::Boolean(false),
+            _ => bail!("'{raw}' is not a boolean, expected true or false"),
+        },
+        KeyKind::Text | KeyKind::Secret => toml::Value::String(raw.to_string()),
+        KeyKind::Choice(choices) => {
+            if !choices.contains(&raw) {
+                bail!("'{raw}' is not one of {}", choices.join(", "));
+            }
+            match raw {
+                "true" => toml::Value::Boolean(true),
+                "false" => toml::Value::Boolean(false),
+                _ => toml::Value::String(raw.to_string()),
+            }
+        }
+    };
+    Ok(value)
+}
+
+fn expand_home(raw: &str) -> PathBuf {
+    match raw.strip_prefix("~/").or_else(|| raw.strip_prefix("~\\")) {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None => PathBuf::from(raw),
+    }
+}
+
 impl Config {
-    pub fn godo_dir() -> PathBuf {
+    /// The legacy `~/.godo` directory, still used on macOS and Windows.
+    pub fn legacy_dir() -> PathBuf {
         dirs::home_dir()
             .expect("Cannot determine home directory")
             .join(".godo")
     }
 
+    /// `GODO_HOME` puts everything into a single directory, like `~/.godo` used to.
+    fn godo_home() -> Option<PathBuf> {
+        std::env::var_os("GODO_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// Resolves a base directory: `GODO_HOME`, the XDG directory on Linux, or `~/.godo`.
+    fn base_dir(xdg: fn() -> Option<PathBuf>) -> PathBuf {
+        if let Some(home) = Self::godo_home() {
+            return home;
+        }
+        if cfg!(target_os = "linux") {
+            if let Some(dir) = xdg() {
+                return dir.join("godo");
+            }
+        }
+        Self::legacy_dir()
+    }
+
+    /// Where `config.toml` lives, `$XDG_CONFIG_HOME/godo` on Linux.
+    pub fn config_dir() -> PathBuf {
+        Self::base_dir(dirs::config_dir)
+    }
+
+    /// Where the release manifest and downloads are cached, `$XDG_CACHE_HOME/godo` on Linux.
+    pub fn cache_dir() -> PathBuf {
+        Self::base_dir(dirs::cache_dir)
+    }
+
+    /// Where engines and other persistent state live, `$XDG_DATA_HOME/godo` on Linux.
+    pub fn data_dir() -> PathBuf {
+        Self::base_dir(dirs::data_dir)
+    }
+
     pub fn config_path() -> PathBuf {
-        Self::godo_dir().join("config.toml")
+        Self::config_dir().join("config.toml")
     }
 
     pub fn manifest_path() -> PathBuf {
-        Self::godo_dir().join("manifest.json")
+        Self::cache_dir().join("manifest.json")
     }
 
     pub fn load() -> Result<Self> {
-        let godo_dir = Self::godo_dir();
-        std::fs::create_dir_all(&godo_dir).context("Failed to create ~/.godo directory")?;
+        Self::migrate_legacy()?;
 
-        let engine_dir = godo_dir.join("engine");
-        std::fs::create_dir_all(&engine_dir).context("Failed to create engine directory")?;
+        let config_dir = Self::config_dir();
+        std::fs::create_dir_all(&config_dir).context("Failed to create config directory")?;
+        std::fs::create_dir_all(Self::cache_dir()).context("Failed to create cache directory")?;
 
         let config_path = Self::config_path();
-        if config_path.exists() {
-            let content =
-                std::fs::read_to_string(&config_path).context("Failed to read config file")?;
-            let config: Config = toml::from_str(&content).context("Failed to parse config file")?;
-            std::fs::create_dir_all(&config.engine_dir)
-                .context("Failed to create engine directory")?;
-            std::fs::create_dir_all(&config.temp_dir).context("Failed to create temp directory")?;
-            Ok(config)
+        let mut config = if config_path.exists() {
+            Self::read_file()?
         } else {
             let config = Config::default_config();
             config.save()?;
-            Ok(config)
+            config
+        };
+
+        config.apply_env();
+        Ok(config)
+    }
+
+    /// Reads and validates `config.toml` as written, without environment overrides.
+    pub fn read_file() -> Result<Self> {
+        let config_path = Self::config_path();
+        if !config_path.exists() {
+            return Ok(Self::default_config());
+        }
+        let content =
+            std::fs::read_to_string(&config_path).context("Failed to read config file")?;
+        Self::parse(&content)
+            .with_context(|| format!("Invalid config file {}", config_path.display()))
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let config: Config = toml::from_str(content).map_err(|e| anyhow::anyhow!("{e}"))?;
+        for (key, path) in [
+            ("engine_dir", &config.engine_dir),
+            ("temp_dir", &config.temp_dir),
+        ] {
+            if !path.is_absolute() {
+                bail!("{key} must be an absolute path, got '{}'", path.display());
+            }
         }
+        for (i, source) in config.sources.iter().enumerate() {
+            // The name prefixes versions as `name/4.3` and folders as `name@4.3.0-stable`
+            let valid = source.name.starts_with(|c: char| c.is_ascii_alphabetic())
+                && source
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid {
+                bail!(
+                    "Invalid source name '{}', use letters, digits, '-' and '_', starting with a letter",
+                    source.name
+                );
+            }
+            if config.sources[..i].iter().any(|s| s.name == source.name) {
+                bail!("Source '{}' is defined more than once", source.name);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Reads `config.toml` as a raw table, so edits keep unknown and unset keys untouched.
+    pub fn read_table() -> Result<toml::Table> {
+        let config_path = Self::config_path();
+        if !config_path.exists() {
+            return toml::Table::try_from(Self::default_config())
+                .context("Failed to serialize config");
+        }
+        let content =
+            std::fs::read_to_string(&config_path).context("Failed to read config file")?;
+        content
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid config file {}: {e}", config_path.display()))
+    }
+
+    /// Validates a raw table and writes it to `config.toml`.
+    pub fn write_table(table: &toml::Table) -> Result<()> {
+        let content = toml::to_string_pretty(table).context("Failed to serialize config")?;
+        Self::parse(&content)?;
+        write_config_file(&content, table.contains_key("github_token"))
+    }
+
+    /// Environment overrides, applied after loading and never written back to the file.
+    fn apply_env(&mut self) {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        if let Some(dir) = var("GODO_ENGINE_DIR") {
+            self.engine_dir = PathBuf::from(dir);
+        }
+        if let Some(dir) = var("GODO_TEMP_DIR") {
+            self.temp_dir = PathBuf::from(dir);
+        }
+        if let Some(token) = var("GITHUB_TOKEN").or_else(|| var("GH_TOKEN")) {
+            self.github_token = Some(token);
+        }
+    }
+
+    /// Moves `config.toml` and `manifest.json` out of `~/.godo` into the XDG directories.
+    /// Engines stay where they are, as the migrated config still points at them.
+    pub fn migrate_legacy() -> Result<()> {
+        let legacy_config = Self::legacy_dir().join("config.toml");
+        let config_path = Self::config_path();
+        if config_path == legacy_config || config_path.exists() || !legacy_config.exists() {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(Self::config_dir()).context("Failed to create config directory")?;
+        std::fs::create_dir_all(Self::cache_dir()).context("Failed to create cache directory")?;
+        move_file(&legacy_config, &config_path).context("Failed to migrate config file")?;
+
+        let legacy_manifest = Self::legacy_dir().join("manifest.json");
+        if legacy_manifest.exists() {
+            let _ = move_file(&legacy_manifest, &Self::manifest_path());
+        }
+
+        eprintln!(
+            "  Moved config from {} to {}",
+            Self::legacy_dir().display(),
+            Self::config_dir().display()
+        );
+        Ok(())
     }
 
     pub fn default_config() -> Self {
-        let godo_dir = Self::godo_dir();
         let temp_dir = if cfg!(target_os = "windows") {
             std::env::var("TEMP")
                 .map(PathBuf::from)
                 .unwrap_or_else(|_| std::env::temp_dir())
                 .join("godo")
+        } else if Self::godo_home().is_some() || cfg!(target_os = "linux") {
+            // Per-user instead of a shared /tmp/godo
+            Self::cache_dir().join("downloads")
         } else {
-            PathBuf::from("/tmp/godo")
+            std::env::temp_dir().join("godo")
         };
 
         Config {
-            engine_dir: godo_dir.join("engine"),
+            engine_dir: Self::data_dir().join("engine"),
             temp_dir,
             invalidate_time: default_invalidate_time(),
             github_token: None,
+            keep_archives: false,
+            default_mono: MonoPreference::default(),
+            auto_set_current: AutoSetCurrent::default(),
+            default_channel: Channel::default(),
+            include_prereleases_in_list: false,
+            launch_logs: default_launch_logs(),
+            sources: Vec::new(),
+            mirrors: Vec::new(),
+            network: NetworkConfig::default(),
+            offline: false,
+            prompter: Prompter::default(),
         }
     }
 
     pub fn save(&self) -> Result<()> {
-        let config_path = Self::config_path();
         let content = toml::to_string_pretty(self).context("Failed to serialize config")?;
-        std::fs::write(&config_path, content).context("Failed to write config file")?;
+        write_config_file(&content, self.github_token.is_some())
+    }
+
+    pub fn source(&self, name: &str) -> Option<&SourceConfig> {
+        self.sources.iter().find(|s| s.name == name)
+    }
+
+    /// Candidate URLs for a download: every matching mirror in order, then the original URL.
+    pub fn mirror_urls(&self, url: &str) -> Vec<String> {
+        let mut urls: Vec<String> = self
+            .mirrors
+            .iter()
+            .filter_map(|m| {
+                url.strip_prefix(&m.pattern)
+                    .map(|rest| format!("{}{rest}", m.replace))
+            })
+            .collect();
+        urls.push(url.to_string());
+        urls
+    }
+
+    /// Fails with a clear message when a network operation is attempted in offline mode.
+    pub fn ensure_online(&self, action: &str) -> Result<()> {
+        if self.offline {
+            bail!("Cannot {action} in offline mode (--offline / GODO_OFFLINE is set)");
+        }
         Ok(())
     }
 
@@ -86,3 +605,74 @@ impl Config {
         self.engine_dir.join("current")
     }
 }
+
+/// Writes `config.toml`, readable only by the owner when it holds a token.
+fn write_config_file(content: &str, has_secret: bool) -> Result<()> {
+    let config_path = Config::config_path();
+    std::fs::create_dir_all(Config::config_dir()).context("Failed to create config directory")?;
+    std::fs::write(&config_path, content).context("Failed to write config file")?;
+
+    #[cfg(unix)]
+    if has_secret {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&config_path, std::fs::Permissions::from_mode(0o600))
+            .context("Failed to restrict config file permissions")?;
+    }
+    #[cfg(not(unix))]
+    let _ = has_secret;
+
+    Ok(())
+}
+
+/// Renames a file, falling back to copy and delete across filesystems.
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if std::fs::rename(from, to).is_err() {
+        std::fs::copy(from, to)?;
+        std::fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_in_plain_seconds() {
+        assert_eq!(parse_duration("90").unwrap(), 90);
+        assert_eq!(parse_duration(" 0 ").unwrap(), 0);
+    }
+
+    #[test]
+    fn duration_with_units_adds_up() {
+        assert_eq!(parse_duration("15m").unwrap(), 900);
+        assert_eq!(parse_duration("1h30m").unwrap(), 5400);
+        assert_eq!(parse_duration("1w1d1s").unwrap(), 604800 + 86400 + 1);
+    }
+
+    #[test]
+    fn duration_rejects_malformed_input() {
+        for input in ["", "h", "1x", "3h5", "-1", "1.5h", "1 h"] {
+            assert!(parse_duration(input).is_err(), "accepted '{input}'");
+        }
+    }
+
+    #[test]
+    fn duration_rejects_overflow() {
+        assert!(parse_duration("99999999999999999d").is_err());
+        assert!(parse_duration("18446744073709551615s1s").is_err());
+        assert!(parse_duration("999999999999999999999").is_err());
+        assert_eq!(parse_duration("18446744073709551615s").unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn duration_values_must_fit_in_toml() {
+        let max = parse_key_value(KeyKind::Duration, "9223372036854775807").unwrap();
+        assert_eq!(max, toml::Value::Integer(i64::MAX));
+        let error = parse_key_value(KeyKind::Duration, "18446744073709551615s").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Duration '18446744073709551615s' is too long"
+        );
+    }
+}
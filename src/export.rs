@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::version::GodotVersion;
+
+/// Name Godot gives the export templates of a version, e.g. `4.3.stable` or
+/// `4.2.2.stable.mono`. A zero patch is left out.
+pub fn templates_name(version: &GodotVersion) -> String {
+    let mut name = format!("{}.{}", version.major, version.minor);
+    if version.patch > 0 {
+        name.push_str(&format!(".{}", version.patch));
+    }
+    name.push_str(&format!(".{}", version.pre));
+    if version.mono {
+        name.push_str(".mono");
+    }
+    name
+}
+
+/// Where the engine looks for its export templates: `editor_data` of a self-contained
+/// install, the shared Godot data directory otherwise
+pub fn templates_dir(config: &Config, version: &GodotVersion) -> Result<PathBuf> {
+    let version_dir = config.engine_dir.join(version.folder_name());
+    let base = if crate::settings::is_self_contained(&version_dir) {
+        version_dir.join("editor_data")
+    } else {
+        crate::project::editor_data_dir().context("Cannot determine the Godot data directory")?
+    };
+    Ok(base.join("export_templates").join(templates_name(version)))
+}
+
+/// Whether the templates of a version are installed, judged by the `version.txt` that
+/// comes with them
+pub fn has_templates(dir: &Path) -> bool {
+    dir.join("version.txt").is_file()
+}
+
+/// Whether `name` is the export templates archive of a release, e.g.
+/// `Godot_v4.3-stable_mono_export_templates.tpz`
+pub fn is_templates_asset(name: &str, mono: bool) -> bool {
+    name.ends_with("_export_templates.tpz") && name.contains("_mono_") == mono
+}
+
+/// Names of the presets in the project's `export_presets.cfg`
+pub fn preset_names(project: &Path) -> Result<Vec<String>> {
+    let path = project.join("export_presets.cfg");
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("No export presets in {}", project.display()))?;
+    let mut names = Vec::new();
+    let mut in_preset = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            // `[preset.0.options]` holds options, only `[preset.0]` names the preset
+            in_preset = line.starts_with("[preset.") && !line.contains(".options");
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("name=").filter(|_| in_preset) {
+            names.push(value.trim_matches('"').to_string());
+        }
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    fn presets(content: &str) -> Result<Vec<String>> {
+        let dir = TempDir::new();
+        dir.write("export_presets.cfg", content);
+        preset_names(dir.path())
+    }
+
+    #[test]
+    fn reads_preset_names_but_not_option_names() {
+        let content = r#"[preset.0]
+
+name="Linux"
+platform="Linux"
+
+[preset.0.options]
+
+name="not a preset"
+binary_format/embed_pck=false
+
+[preset.1]
+
+name="Windows Desktop"
+"#;
+        assert_eq!(presets(content).unwrap(), ["Linux", "Windows Desktop"]);
+    }
+
+    #[test]
+    fn ignores_names_outside_presets() {
+        let content = "name=\"top\"\n[other]\nname=\"other\"\n";
+        assert!(presets(content).unwrap().is_empty());
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        assert!(preset_names(TempDir::new().path()).is_err());
+    }
+}
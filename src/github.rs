@@ -1,15 +1,28 @@
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use colored::Colorize;
 use serde::{Deserialize, Serialize};
+use ureq::http::{HeaderMap, StatusCode};
 
-use crate::version::{GodotVersion, VersionQuery};
+use crate::version::{Channel, GodotVersion, VersionQuery};
 
-const GITHUB_API_GODOT: &str = "https://api.github.com/repos/godotengine/godot/releases";
-const GITHUB_API_BUILDS: &str = "https://api.github.com/repos/godotengine/godot-builds/releases";
+const GITHUB_API_RATE_LIMIT: &str = "https://api.github.com/rate_limit";
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GithubRelease {
     pub tag_name: String,
     pub assets: Vec<GithubAsset>,
+    /// Name of the release source, `None` for official Godot releases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl GithubRelease {
+    pub fn version(&self) -> Option<GodotVersion> {
+        match &self.source {
+            Some(source) => GodotVersion::from_source_tag(source, &self.tag_name),
+            None => GodotVersion::from_tag(&self.tag_name),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -19,6 +32,69 @@ pub struct GithubAsset {
     pub size: u64,
 }
 
+/// Rate limit status of the GitHub API, as reported by the `X-RateLimit-*` headers.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Unix timestamp (in seconds) at which the quota resets
+    pub reset: Option<u64>,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        RateLimit {
+            limit: header_u64(headers, "X-RateLimit-Limit"),
+            remaining: header_u64(headers, "X-RateLimit-Remaining"),
+            reset: header_u64(headers, "X-RateLimit-Reset"),
+        }
+    }
+
+    /// Seconds left until the quota resets, if known.
+    pub fn resets_in(&self) -> Option<u64> {
+        let reset = self.reset?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        Some(reset.saturating_sub(now))
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = match (self.remaining, self.limit) {
+            (Some(remaining), Some(limit)) => format!("{remaining}/{limit} requests remaining"),
+            (Some(remaining), None) => format!("{remaining} requests remaining"),
+            _ => "unknown quota".to_string(),
+        };
+        if let Some(secs) = self.resets_in() {
+            text.push_str(&format!(", resets in {}", format_duration(secs)));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubErrorBody {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RateLimitResponse {
+    resources: RateLimitResources,
+}
+
+#[derive(Debug, Deserialize)]
+struct RateLimitResources {
+    core: RateLimitCore,
+}
+
+#[derive(Debug, Deserialize)]
+struct RateLimitCore {
+    limit: u64,
+    remaining: u64,
+    reset: u64,
+}
+
 pub fn fetch_releases_cached(config: &crate::config::Config) -> Result<Vec<GithubRelease>> {
     let manifest_path = crate::config::Config::manifest_path();
 
@@ -28,66 +104,128 @@ pub fn fetch_releases_cached(config: &crate::config::Config) -> Result<Vec<Githu
             .context("Failed to get manifest modification time")?;
         let elapsed = modified.elapsed().unwrap_or(std::time::Duration::MAX);
         if elapsed.as_secs() < config.invalidate_time {
-            let content =
-                std::fs::read_to_string(&manifest_path).context("Failed to read manifest cache")?;
-            let releases: Vec<GithubRelease> =
-                serde_json::from_str(&content).context("Failed to parse manifest cache")?;
-            return Ok(releases);
+            return load_manifest();
+        }
+        if config.offline {
+            eprintln!(
+                "{} Using stale manifest cache ({}h old) in offline mode",
+                "!".yellow(),
+                elapsed.as_secs() / 3600
+            );
+            return load_manifest();
         }
+    } else if config.offline {
+        bail!("No cached release manifest in offline mode. Run 'godo update' while online first.");
     }
 
-    let releases = fetch_releases_remote(config.github_token.as_deref())?;
+    let releases = match fetch_releases_remote(config) {
+        Ok(r) => r,
+        Err(e) if manifest_path.exists() => {
+            eprintln!("{} {e:#}", "!".yellow());
+            eprintln!("{} Falling back to the stale manifest cache", "!".yellow());
+            return load_manifest();
+        }
+        Err(e) => return Err(e),
+    };
     save_manifest(&releases)?;
     Ok(releases)
 }
 
-pub fn fetch_releases_remote(token: Option<&str>) -> Result<Vec<GithubRelease>> {
-    let godot_releases = fetch_releases_from(GITHUB_API_GODOT, token)?;
-    let builds_releases = fetch_releases_from(GITHUB_API_BUILDS, token)?;
-
+pub fn fetch_releases_remote(config: &crate::config::Config) -> Result<Vec<GithubRelease>> {
     let mut seen = std::collections::HashSet::new();
     let mut all_releases = Vec::new();
+    let sources = crate::source::sources(config);
+    let mut last_error = None;
+    let mut failed = 0;
+
+    for source in &sources {
+        let namespace = source.namespace().map(str::to_string);
+        let releases = match source.fetch_releases().with_context(|| match &namespace {
+            Some(name) => format!("Failed to fetch releases from source '{name}'"),
+            None => "Failed to fetch official releases".to_string(),
+        }) {
+            Ok(releases) => releases,
+            Err(e) => {
+                // One unreachable source should not hide the others. Its releases from the
+                // previous manifest are kept, if there is one.
+                eprintln!("{} {e:#}", "!".yellow());
+                last_error = Some(e);
+                failed += 1;
+                load_manifest()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|r| r.source == namespace)
+                    .collect()
+            }
+        };
 
-    for r in godot_releases.into_iter().chain(builds_releases) {
-        if seen.insert(r.tag_name.clone()) {
-            all_releases.push(r);
+        for mut r in releases {
+            r.source = namespace.clone();
+            if seen.insert((r.source.clone(), r.tag_name.clone())) {
+                all_releases.push(r);
+            }
         }
     }
 
-    Ok(all_releases)
+    match last_error {
+        Some(e) if failed == sources.len() => Err(e),
+        _ => Ok(all_releases),
+    }
 }
 
-fn save_manifest(releases: &[GithubRelease]) -> Result<()> {
+pub fn load_manifest() -> Result<Vec<GithubRelease>> {
+    let manifest_path = crate::config::Config::manifest_path();
+    let content =
+        std::fs::read_to_string(&manifest_path).context("Failed to read manifest cache")?;
+    let releases: Vec<GithubRelease> =
+        serde_json::from_str(&content).context("Failed to parse manifest cache")?;
+    Ok(releases)
+}
+
+pub fn save_manifest(releases: &[GithubRelease]) -> Result<()> {
     let manifest_path = crate::config::Config::manifest_path();
     let content = serde_json::to_string(releases).context("Failed to serialize manifest")?;
     std::fs::write(&manifest_path, content).context("Failed to write manifest cache")?;
     Ok(())
 }
 
-fn fetch_releases_from(base_url: &str, token: Option<&str>) -> Result<Vec<GithubRelease>> {
+/// Fetches every page of a GitHub-style releases endpoint. `service` names it in errors,
+/// `page_size_param` is `per_page` for GitHub and `limit` for Gitea, `auth` the full
+/// `Authorization` value.
+pub fn fetch_release_pages(
+    service: &str,
+    base_url: &str,
+    page_size_param: &str,
+    page_size: usize,
+    auth: Option<&str>,
+) -> Result<Vec<GithubRelease>> {
     let mut all_releases = Vec::new();
     let mut page = 1;
 
-    let agent = ureq::agent();
+    let agent = crate::http::agent();
 
     loop {
-        let url = format!("{base_url}?per_page=100&page={page}");
+        let url = format!("{base_url}?{page_size_param}={page_size}&page={page}");
         let mut request = agent.get(&url);
 
-        if let Some(t) = token {
-            request = request.header("Authorization", &format!("Bearer {t}"));
+        if let Some(auth) = auth {
+            request = request.header("Authorization", auth);
         }
 
         let mut response = request
             .call()
-            .context("Failed to fetch releases from GitHub")?;
+            .with_context(|| format!("Failed to fetch releases from {service}"))?;
+
+        if !response.status().is_success() {
+            return Err(api_error(&mut response, service, auth.is_some()));
+        }
 
         let body = response
             .body_mut()
             .read_to_string()
             .context("Failed to read response body")?;
-        let releases: Vec<GithubRelease> =
-            serde_json::from_str(&body).context("Failed to parse GitHub response")?;
+        let releases: Vec<GithubRelease> = serde_json::from_str(&body)
+            .with_context(|| format!("Failed to parse {service} response"))?;
 
         if releases.is_empty() {
             break;
@@ -96,7 +234,7 @@ fn fetch_releases_from(base_url: &str, token: Option<&str>) -> Result<Vec<Github
         let count = releases.len();
         all_releases.extend(releases);
 
-        if count < 100 {
+        if count < page_size {
             break;
         }
         page += 1;
@@ -105,14 +243,111 @@ fn fetch_releases_from(base_url: &str, token: Option<&str>) -> Result<Vec<Github
     Ok(all_releases)
 }
 
+/// Queries the current API quota. This endpoint does not count against the limit itself.
+pub fn fetch_rate_limit(token: Option<&str>) -> Result<RateLimit> {
+    let mut request = crate::http::agent().get(GITHUB_API_RATE_LIMIT);
+    if let Some(t) = token {
+        request = request.header("Authorization", &format!("Bearer {t}"));
+    }
+
+    let mut response = request
+        .call()
+        .context("Failed to query GitHub rate limit")?;
+    if !response.status().is_success() {
+        return Err(api_error(&mut response, OFFICIAL_SERVICE, token.is_some()));
+    }
+
+    let body: RateLimitResponse = response
+        .body_mut()
+        .read_json()
+        .context("Failed to parse GitHub rate limit response")?;
+    Ok(RateLimit {
+        limit: Some(body.resources.core.limit),
+        remaining: Some(body.resources.core.remaining),
+        reset: Some(body.resources.core.reset),
+    })
+}
+
+/// Name of the official release host in errors
+pub const OFFICIAL_SERVICE: &str = "GitHub";
+
+/// Turns an unsuccessful API response of `service` into an error, explaining rate limits.
+pub fn api_error(
+    response: &mut ureq::http::Response<ureq::Body>,
+    service: &str,
+    authenticated: bool,
+) -> anyhow::Error {
+    let status = response.status();
+    let rate_limit = RateLimit::from_headers(response.headers());
+    let retry_after = header_u64(response.headers(), "Retry-After");
+    let message = response
+        .body_mut()
+        .read_json::<GithubErrorBody>()
+        .map(|b| b.message)
+        .unwrap_or_default();
+
+    let rate_limited = (status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS)
+        && (rate_limit.remaining == Some(0)
+            || retry_after.is_some()
+            || message.to_lowercase().contains("rate limit"));
+
+    if !rate_limited {
+        if message.is_empty() {
+            return anyhow!("{service} API returned {status}");
+        }
+        return anyhow!("{service} API returned {status}: {message}");
+    }
+
+    let mut text = format!("{service} API rate limit exceeded");
+    if let Some(secs) = retry_after.or_else(|| rate_limit.resets_in()) {
+        text.push_str(&format!(", retry in {}", format_duration(secs)));
+    }
+    if authenticated {
+        text.push_str(". The quota of the configured token is used up.");
+    } else if service != OFFICIAL_SERVICE {
+        text.push_str(&format!(
+            ". Set `token` of source '{service}' to raise the limit."
+        ));
+    } else {
+        text.push_str(&format!(
+            ". Set `github_token` in {} to raise the limit.",
+            crate::config::Config::config_path().display()
+        ));
+    }
+    anyhow!(text)
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.trim().parse().ok())
+}
+
+fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m", secs.div_ceil(60))
+    } else {
+        format!("{secs}s")
+    }
+}
+
 pub fn find_matching_release<'a>(
     releases: &'a [GithubRelease],
     query: &VersionQuery,
+    channel: Channel,
 ) -> Result<&'a GithubRelease> {
     let mut matched: Vec<&GithubRelease> = Vec::new();
+    // A release holds the Mono and the standard build alike
+    let query = VersionQuery {
+        mono: None,
+        ..query.clone()
+    };
 
     for release in releases {
-        if let Some(ver) = GodotVersion::from_tag(&release.tag_name) {
+        if let Some(ver) = release.version() {
             if query.matches_loose(&ver) {
                 matched.push(release);
             }
@@ -124,18 +359,18 @@ pub fn find_matching_release<'a>(
     }
 
     matched.sort_by(|a, b| {
-        let va = GodotVersion::from_tag(&a.tag_name).unwrap();
-        let vb = GodotVersion::from_tag(&b.tag_name).unwrap();
+        let va = a.version().unwrap();
+        let vb = b.version().unwrap();
         vb.cmp(&va)
     });
 
     if query.pre.is_none() {
-        let stable = matched.iter().find(|r| {
-            GodotVersion::from_tag(&r.tag_name)
-                .map(|v| v.is_stable())
+        let preferred = matched.iter().find(|r| {
+            r.version()
+                .map(|v| channel.accepts(&v.pre))
                 .unwrap_or(false)
         });
-        if let Some(release) = stable {
+        if let Some(release) = preferred {
             return Ok(release);
         }
     }
@@ -143,19 +378,52 @@ pub fn find_matching_release<'a>(
     Ok(matched[0])
 }
 
+/// Finds the newest stable release in the same major.minor that is newer than `version`.
+pub fn find_newer_patch(
+    releases: &[GithubRelease],
+    version: &GodotVersion,
+) -> Option<GodotVersion> {
+    releases
+        .iter()
+        .filter_map(|r| r.version())
+        .filter(|v| {
+            v.is_stable()
+                && v.source == version.source
+                && v.major == version.major
+                && v.minor == version.minor
+                && v > version
+        })
+        .max()
+}
+
 #[derive(Debug)]
 pub struct PlatformAssets<'a> {
     pub main_asset: &'a GithubAsset,
     pub console_asset: Option<&'a GithubAsset>,
 }
 
+/// Finds the assets for this platform. Sources may supply an `asset_pattern`
+/// instead of relying on the official naming scheme.
 pub fn find_platform_assets<'a>(
     assets: &'a [GithubAsset],
     mono: bool,
+    pattern: Option<&str>,
 ) -> Result<PlatformAssets<'a>> {
     let os = std::env::consts::OS;
     let arch = std::env::consts::ARCH;
 
+    if let Some(pattern) = pattern {
+        let pattern = expand_asset_pattern(pattern, mono, os, arch);
+        let main_asset = assets
+            .iter()
+            .find(|a| wildcard_match(&pattern, &a.name))
+            .context(format!("No asset matches pattern '{pattern}'"))?;
+        return Ok(PlatformAssets {
+            main_asset,
+            console_asset: None,
+        });
+    }
+
     let main_asset = assets
         .iter()
         .find(|a| is_main_platform_asset(&a.name, mono, os, arch))
@@ -177,6 +445,32 @@ pub fn find_platform_assets<'a>(
     })
 }
 
+fn expand_asset_pattern(pattern: &str, mono: bool, os: &str, arch: &str) -> String {
+    pattern
+        .replace("{os}", os)
+        .replace("{arch}", arch)
+        .replace("{mono}", if mono { "mono" } else { "" })
+}
+
+/// Matches `name` against a pattern where `*` stands for any run of characters.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, rest) = parts.split_first().unwrap();
+    let Some(mut remaining) = name.strip_prefix(first) else {
+        return false;
+    };
+    let Some((last, middle)) = rest.split_last() else {
+        return remaining.is_empty();
+    };
+    for part in middle {
+        match remaining.find(part) {
+            Some(idx) => remaining = &remaining[idx + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
 fn is_main_platform_asset(name: &str, mono: bool, os: &str, arch: &str) -> bool {
     let lower = name.to_lowercase();
     let is_mono = lower.contains("mono");
@@ -0,0 +1,243 @@
+use anyhow::{Context, Result};
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::OnceLock;
+use std::time::Duration;
+use ureq::tls::{Certificate, PemItem, RootCerts, TlsConfig};
+use ureq::Proxy;
+
+use crate::config::{Config, NetworkConfig};
+
+const DEFAULT_CONNECT_TIMEOUT: u64 = 30;
+const DEFAULT_READ_TIMEOUT: u64 = 60;
+
+/// Common locations of the system certificate bundle on Unix systems
+const SYSTEM_CA_BUNDLES: [&str; 4] = [
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/ssl/cert.pem",
+];
+
+static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+
+/// Builds the shared agent from the `[network]` config. Must be called before any request.
+pub fn init(config: &Config) -> Result<()> {
+    let agent = build_agent(&config.network)?;
+    let _ = AGENT.set(agent);
+    Ok(())
+}
+
+/// The agent every HTTP request goes through. Non-2xx responses are returned
+/// instead of turned into errors, so callers must check the status.
+pub fn agent() -> &'static ureq::Agent {
+    AGENT.get_or_init(|| {
+        build_agent(&NetworkConfig::default()).expect("Default HTTP agent must build")
+    })
+}
+
+fn build_agent(network: &NetworkConfig) -> Result<ureq::Agent> {
+    let mut builder = ureq::Agent::config_builder()
+        .http_status_as_error(false)
+        .proxy(proxy(network)?)
+        .timeout_connect(Some(Duration::from_secs(
+            network.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+        )))
+        .timeout_recv_response(Some(read_timeout(network)))
+        .timeout_recv_body(Some(read_timeout(network)));
+
+    // ureq trusts either its built-in roots or a list of certificates, so a bundle replaces
+    // the built-in roots and the system store is kept next to it
+    if let Some(bundle) = &network.ca_bundle {
+        let mut certs = system_certs();
+        certs.extend(read_certs(bundle)?);
+        builder = builder.tls_config(
+            TlsConfig::builder()
+                .root_certs(RootCerts::new_with_certs(&certs))
+                .build(),
+        );
+    }
+
+    Ok(builder.build().into())
+}
+
+/// Resolves the proxy: `network.proxy` overrides `HTTPS_PROXY` and friends,
+/// `network.no_proxy` overrides `NO_PROXY`. An empty `proxy` disables proxying.
+fn proxy(network: &NetworkConfig) -> Result<Option<Proxy>> {
+    let proxy = match network.proxy.as_deref() {
+        Some("") => return Ok(None),
+        Some(url) => Proxy::new(url).with_context(|| format!("Invalid proxy URL '{url}'"))?,
+        None => match Proxy::try_from_env() {
+            Some(p) => p,
+            None => return Ok(None),
+        },
+    };
+
+    // The environment proxy already carries NO_PROXY
+    if network.proxy.is_none() && network.no_proxy.is_none() {
+        return Ok(Some(proxy));
+    }
+
+    let no_proxy = network
+        .no_proxy
+        .clone()
+        .or_else(|| std::env::var("NO_PROXY").ok())
+        .or_else(|| std::env::var("no_proxy").ok())
+        .unwrap_or_default();
+
+    let mut builder = Proxy::builder(proxy.protocol())
+        .host(proxy.host())
+        .port(proxy.port());
+    if let Some(username) = proxy.username() {
+        builder = builder.username(username);
+    }
+    if let Some(password) = proxy.password() {
+        builder = builder.password(password);
+    }
+    for expr in no_proxy.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        builder = builder.no_proxy(expr);
+    }
+    Ok(Some(
+        builder.build().context("Invalid proxy configuration")?,
+    ))
+}
+
+fn read_timeout(network: &NetworkConfig) -> Duration {
+    Duration::from_secs(network.read_timeout.unwrap_or(DEFAULT_READ_TIMEOUT))
+}
+
+/// Reads a download body on a helper thread, failing once no data arrives for the read
+/// timeout. The agent's body timeout limits the whole body, which a large archive on a slow
+/// link exceeds legitimately, so downloads lift it and use this instead.
+///
+/// A blocking read cannot be interrupted, so after a timeout the thread stays blocked until
+/// the server sends more data or the connection closes, then exits as nothing receives its
+/// chunks anymore. Each stalled download leaks that thread and its connection until then.
+pub fn stall_guard(body: ureq::Body) -> StallGuard {
+    let (sender, receiver) = mpsc::sync_channel(4);
+    std::thread::spawn(move || {
+        let mut reader = body.into_reader();
+        loop {
+            let mut chunk = vec![0u8; 64 * 1024];
+            let result = reader.read(&mut chunk).map(|n| {
+                chunk.truncate(n);
+                chunk
+            });
+            let last = !matches!(&result, Ok(chunk) if !chunk.is_empty());
+            if sender.send(result).is_err() || last {
+                break;
+            }
+        }
+    });
+    StallGuard {
+        receiver,
+        idle: agent()
+            .config()
+            .timeouts()
+            .recv_response
+            .unwrap_or(Duration::from_secs(DEFAULT_READ_TIMEOUT)),
+        chunk: Vec::new(),
+        pos: 0,
+        done: false,
+    }
+}
+
+pub struct StallGuard {
+    receiver: Receiver<io::Result<Vec<u8>>>,
+    idle: Duration,
+    chunk: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl Read for StallGuard {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.chunk.len() {
+            if self.done {
+                return Ok(0);
+            }
+            match self.receiver.recv_timeout(self.idle) {
+                Ok(Ok(chunk)) if chunk.is_empty() => {
+                    self.done = true;
+                    return Ok(0);
+                }
+                Ok(Ok(chunk)) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                Ok(Err(e)) => return Err(e),
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("no data received for {}s", self.idle.as_secs()),
+                    ))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(io::ErrorKind::UnexpectedEof.into())
+                }
+            }
+        }
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+fn read_certs(path: &Path) -> Result<Vec<Certificate<'static>>> {
+    let pem = std::fs::read(path)
+        .with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+    let mut certs = Vec::new();
+    for item in ureq::tls::parse_pem(&pem) {
+        if let PemItem::Certificate(cert) = item.context("Failed to parse CA bundle")? {
+            certs.push(cert);
+        }
+    }
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in CA bundle {}", path.display());
+    }
+    Ok(certs)
+}
+
+/// Certificates of the system store on Unix, so roots installed system-wide stay trusted
+/// next to an extra CA bundle. Empty where no bundle file exists, e.g. on Windows and macOS.
+fn system_certs() -> Vec<Certificate<'static>> {
+    let env_bundle = std::env::var("SSL_CERT_FILE").ok();
+    let bundle = env_bundle
+        .iter()
+        .map(String::as_str)
+        .chain(SYSTEM_CA_BUNDLES)
+        .map(Path::new)
+        .find(|p| p.is_file());
+    bundle.and_then(|p| read_certs(p).ok()).unwrap_or_default()
+}
+
+/// Human readable summary of the effective network settings.
+pub fn describe(network: &NetworkConfig) -> Vec<(&'static str, String)> {
+    let proxy = match agent().config().proxy() {
+        Some(p) => format!(
+            "{}://{}:{}",
+            p.protocol().to_string().to_lowercase(),
+            p.host(),
+            p.port()
+        ),
+        None => "none".to_string(),
+    };
+    let ca = match &network.ca_bundle {
+        Some(p) => format!("system + {}", p.display()),
+        None => "built-in".to_string(),
+    };
+    vec![
+        ("Proxy", proxy),
+        ("CA roots", ca),
+        (
+            "Timeouts",
+            format!(
+                "connect {}s, read {}s",
+                network.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+                network.read_timeout.unwrap_or(DEFAULT_READ_TIMEOUT)
+            ),
+        ),
+    ]
+}
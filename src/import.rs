@@ -0,0 +1,349 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
+use crate::link;
+use crate::trash::copy_dir;
+use crate::version::GodotVersion;
+
+/// How an engine found by `godo import` is brought into the engine directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportMode {
+    /// Leave it where it is and link to it, see `godo link`
+    Link,
+    Copy,
+    Move,
+}
+
+/// How deep below a scanned directory executables are looked for
+const MAX_DEPTH: usize = 5;
+
+/// An engine found while scanning
+#[derive(Debug)]
+pub struct Found {
+    /// The executable, or the `.app` bundle on macOS
+    pub target: PathBuf,
+    pub version: GodotVersion,
+    /// Build name reported by `--version`, e.g. `official` or `steam`
+    pub build: String,
+}
+
+impl Found {
+    /// The version it is imported as. Official builds that are copied or moved become regular
+    /// installs, everything else keeps a label like linked engines do.
+    pub fn import_as(&self, mode: ImportMode) -> GodotVersion {
+        let label = (mode == ImportMode::Link || self.build != "official")
+            .then(|| link::default_label(&self.build));
+        GodotVersion {
+            label,
+            ..self.version.clone()
+        }
+    }
+
+    /// What is copied or moved: the folder of the engine when it has one to itself, so data
+    /// such as `GodotSharp` comes along, otherwise the executable alone
+    pub fn payload(&self, roots: &[PathBuf]) -> PathBuf {
+        if self.target.extension().is_some_and(|e| e == "app") {
+            return self.target.clone();
+        }
+        match self.target.parent() {
+            Some(parent)
+                if !roots.iter().any(|r| r == parent) && executables_in(parent, 0).len() == 1 =>
+            {
+                parent.to_path_buf()
+            }
+            _ => self.target.clone(),
+        }
+    }
+
+    /// Data the engine needs that sits next to an executable imported on its own, like in
+    /// Steam's `Godot Engine` folder: `GodotSharp` of Mono builds and the `.pck` of the
+    /// executable
+    pub fn companions(&self, roots: &[PathBuf]) -> Vec<PathBuf> {
+        let payload = self.payload(roots);
+        let Some(parent) = payload.parent().filter(|_| payload == self.target) else {
+            return Vec::new();
+        };
+        let mut companions = Vec::new();
+        let sharp = parent.join("GodotSharp");
+        if self.version.mono && sharp.is_dir() {
+            companions.push(sharp);
+        }
+        let mut pcks = vec![self.target.with_extension("pck")];
+        if let Some(name) = self.target.file_name() {
+            let mut name = name.to_os_string();
+            name.push(".pck");
+            pcks.push(parent.join(name));
+        }
+        pcks.dedup();
+        companions.extend(pcks.into_iter().filter(|p| p.is_file()));
+        companions
+    }
+
+    /// Whether Steam manages the engine, which then must stay where it is
+    pub fn is_steam(&self) -> bool {
+        self.target
+            .components()
+            .any(|c| c.as_os_str() == "steamapps")
+    }
+}
+
+/// Places where other tools and installers keep Godot, in the order they are reported
+pub fn default_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    let home = dirs::home_dir();
+
+    // Steam, including extra libraries listed in libraryfolders.vdf
+    let mut steam_dirs: Vec<PathBuf> = Vec::new();
+    if let Some(home) = &home {
+        steam_dirs.push(home.join(".steam/steam"));
+        steam_dirs.push(home.join(".local/share/Steam"));
+        steam_dirs.push(home.join("Library/Application Support/Steam"));
+    }
+    steam_dirs.push(PathBuf::from(r"C:\Program Files (x86)\Steam"));
+    let mut libraries = steam_dirs.clone();
+    for steam in &steam_dirs {
+        let vdf = steam.join("steamapps").join("libraryfolders.vdf");
+        let Ok(content) = std::fs::read_to_string(vdf) else {
+            continue;
+        };
+        for line in content.lines() {
+            let mut fields = line.split('"').filter(|f| !f.trim().is_empty());
+            if fields.next() == Some("path") {
+                if let Some(path) = fields.next() {
+                    libraries.push(PathBuf::from(path.replace("\\\\", "\\")));
+                }
+            }
+        }
+    }
+    roots.extend(
+        libraries
+            .iter()
+            .map(|l| l.join("steamapps").join("common").join("Godot Engine")),
+    );
+
+    // Flatpak builds are left out: they only run inside `flatpak run`, which godo does not use
+    if let Some(home) = &home {
+        roots.push(home.join("Applications"));
+    }
+    roots.push(PathBuf::from("/Applications"));
+    roots.push(PathBuf::from("/opt"));
+
+    // Other version managers: godotenv, gdvm and Godots
+    if let Some(config) = dirs::config_dir() {
+        roots.push(config.join("godotenv"));
+    }
+    if let Some(home) = &home {
+        roots.push(home.join(".gdvm"));
+    }
+    if let Some(data) = crate::project::editor_data_dir() {
+        roots.push(data.join("app_userdata").join("Godots").join("versions"));
+    }
+
+    roots.retain(|r| r.is_dir());
+    roots
+}
+
+/// Runs `--version` on every Godot executable below `roots`. Executables that don't
+/// report a version are skipped, as are those inside `skip`.
+pub fn scan(roots: &[PathBuf], skip: &Path) -> Vec<Found> {
+    let skip = skip.canonicalize().unwrap_or_else(|_| skip.to_path_buf());
+    let mut targets: Vec<PathBuf> = roots
+        .iter()
+        .flat_map(|root| executables_in(root, MAX_DEPTH))
+        .filter_map(|p| p.canonicalize().ok())
+        .filter(|p| !p.starts_with(&skip))
+        .collect();
+    targets.sort();
+    targets.dedup();
+
+    targets
+        .into_iter()
+        .filter_map(|target| {
+            let (version, build) = link::detect_version(&link::executable_of(&target))?;
+            Some(Found {
+                target,
+                version,
+                build,
+            })
+        })
+        .collect()
+}
+
+/// Godot executables and `.app` bundles in `dir` and up to `depth` levels below it.
+/// Console wrappers, libraries and other files named after Godot are left out.
+fn executables_in(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if name.starts_with("godot") && name.ends_with(".app") {
+                found.push(path);
+            } else if depth > 0 {
+                found.extend(executables_in(&path, depth - 1));
+            }
+        } else if file_type.is_file() && name.starts_with("godot") && is_engine_file(&path, &name) {
+            found.push(path);
+        }
+    }
+    found
+}
+
+fn is_engine_file(path: &Path, name: &str) -> bool {
+    if name.contains("console") {
+        return false;
+    }
+    if cfg!(windows) {
+        return name.ends_with(".exe");
+    }
+    let skipped = [
+        ".so", ".dll", ".pck", ".zip", ".txt", ".json", ".cfg", ".desktop", ".sh",
+    ];
+    if skipped.iter().any(|ext| name.ends_with(ext)) {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        true
+    }
+}
+
+/// Copies or moves `payload` and its `companions` into `version_dir`. Folders keep their
+/// content, a lone executable becomes `godot` with its `.pck` renamed to match, and a bundle
+/// keeps its name.
+pub fn transfer(
+    payload: &Path,
+    companions: &[PathBuf],
+    version_dir: &Path,
+    mode: ImportMode,
+) -> Result<()> {
+    let is_bundle = payload.extension().is_some_and(|e| e == "app");
+    let dest = if payload.is_dir() && !is_bundle {
+        version_dir.to_path_buf()
+    } else if is_bundle {
+        version_dir.join("Godot.app")
+    } else {
+        version_dir.join(if cfg!(windows) { "godot.exe" } else { "godot" })
+    };
+
+    if dest != version_dir {
+        std::fs::create_dir_all(version_dir).context("Failed to create engine directory")?;
+    }
+    let mut items = vec![(payload.to_path_buf(), dest)];
+    for companion in companions {
+        let dest = if companion.is_dir() {
+            version_dir.join(companion.file_name().unwrap_or_default())
+        } else {
+            version_dir.join("godot.pck")
+        };
+        items.push((companion.clone(), dest));
+    }
+
+    // Originals are only removed once everything is in place, a failure puts moved items back
+    let mut moved = Vec::new();
+    let mut copied = Vec::new();
+    for (from, to) in &items {
+        if mode == ImportMode::Move && std::fs::rename(from, to).is_ok() {
+            moved.push((from, to));
+            continue;
+        }
+        let result = if from.is_dir() {
+            copy_dir(from, to)
+        } else {
+            std::fs::copy(from, to).map(|_| ()).map_err(Into::into)
+        };
+        if let Err(e) = result {
+            for (from, to) in moved {
+                let _ = std::fs::rename(to, from);
+            }
+            let _ = std::fs::remove_dir_all(version_dir);
+            return Err(e).with_context(|| format!("Failed to copy {}", from.display()));
+        }
+        copied.push(from);
+    }
+
+    if mode == ImportMode::Move {
+        for from in copied {
+            let removed = if from.is_dir() {
+                std::fs::remove_dir_all(from)
+            } else {
+                std::fs::remove_file(from)
+            };
+            removed.with_context(|| {
+                format!(
+                    "Copied, but failed to remove the original at {}",
+                    from.display()
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    fn found(target: PathBuf, mono: bool) -> Found {
+        let version = GodotVersion::from_folder("4.2.2-stable").unwrap();
+        Found {
+            target,
+            version: GodotVersion { mono, ..version },
+            build: "steam".to_string(),
+        }
+    }
+
+    #[test]
+    fn executable_in_a_root_brings_its_data() {
+        let dir = TempDir::new();
+        let exe = dir.write("godot.x11.opt.tools.64", "");
+        let pck = dir.write("godot.x11.opt.tools.64.pck", "");
+        dir.write("GodotSharp/Api/GodotSharp.dll", "");
+        let roots = [dir.path().to_path_buf()];
+
+        let mono = found(exe.clone(), true);
+        assert_eq!(mono.payload(&roots), exe);
+        assert_eq!(
+            mono.companions(&roots),
+            [dir.path().join("GodotSharp"), pck.clone()]
+        );
+        assert_eq!(found(exe, false).companions(&roots), [pck]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn engine_with_a_folder_of_its_own_has_no_companions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new();
+        let exe = dir.write("engine/Godot_v4.2.2-stable_mono_linux.x86_64", "");
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+        dir.write("engine/GodotSharp/Api/GodotSharp.dll", "");
+        let engine = found(exe, true);
+        let roots = [dir.path().to_path_buf()];
+        assert_eq!(engine.payload(&roots), dir.path().join("engine"));
+        assert!(engine.companions(&roots).is_empty());
+    }
+
+    #[test]
+    fn steam_engines_are_recognised_by_their_library() {
+        let steam = PathBuf::from("/home/u/.steam/steam/steamapps/common/Godot Engine/godot");
+        assert!(found(steam, false).is_steam());
+        assert!(!found(PathBuf::from("/opt/godot/godot"), false).is_steam());
+    }
+}
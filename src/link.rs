@@ -0,0 +1,175 @@
+use anyhow::{bail, Context, Result};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::version::GodotVersion;
+
+/// Label given to linked official builds, whose `--version` output names no other build
+pub const DEFAULT_LABEL: &str = "linked";
+
+/// How long an executable gets to answer `--version` before it is killed
+const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Names of the links an engine directory of a linked engine consists of
+#[cfg(target_os = "windows")]
+const LINK_NAMES: [&str; 2] = ["godot.exe", "Godot.app"];
+#[cfg(not(target_os = "windows"))]
+const LINK_NAMES: [&str; 2] = ["godot", "Godot.app"];
+
+/// Runs `<executable> --version` and parses what it prints, e.g.
+/// `4.3.stable.official.77dcf97d8` or `4.2.2.stable.mono.steam.15073afe3`.
+/// Returns the version and the build name, such as `official`, `steam` or `custom_build`.
+/// Executables that do not answer within `VERSION_TIMEOUT` are killed and yield `None`.
+pub fn detect_version(executable: &Path) -> Option<(GodotVersion, String)> {
+    let mut command = Command::new(executable);
+    command
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    // A group of its own lets a timeout also stop whatever a wrapper script started
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command.spawn().ok()?;
+    // Read on a thread so a chatty executable cannot block on a full pipe while we wait
+    let mut pipe = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = pipe.read_to_end(&mut output);
+        output
+    });
+
+    let deadline = Instant::now() + VERSION_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            _ => {
+                #[cfg(unix)]
+                if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+                    // SAFETY: kill has no memory safety requirements
+                    unsafe { libc::kill(-pid, libc::SIGKILL) };
+                }
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    let output = reader.join().ok()?;
+    let stdout = String::from_utf8_lossy(&output);
+    stdout
+        .lines()
+        .rev()
+        .find_map(|line| parse_version_output(line.trim()))
+}
+
+fn parse_version_output(line: &str) -> Option<(GodotVersion, String)> {
+    let parts: Vec<&str> = line.split('.').collect();
+    let numbers = parts
+        .iter()
+        .take_while(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+        .count();
+    if !(2..=3).contains(&numbers) {
+        return None;
+    }
+    let mut rest = parts[numbers..].iter();
+    let status = rest.next()?;
+    // Builds from source report a bare `dev`
+    let number = parts[..numbers].join(".");
+    let version = GodotVersion::from_tag(&format!("{number}-{status}"))
+        .or_else(|| GodotVersion::from_tag(&format!("{number}-{status}0")))?;
+    let mut build = rest.next().copied().unwrap_or(DEFAULT_LABEL);
+    let mono = build == "mono";
+    if mono {
+        build = rest.next().copied().unwrap_or(DEFAULT_LABEL);
+    }
+    Some((GodotVersion { mono, ..version }, build.to_string()))
+}
+
+/// Label for a build named `build` by `--version`: the build name, shortened for builds from
+/// source, or `linked` for official builds
+pub fn default_label(build: &str) -> String {
+    let label = match build {
+        "custom_build" => "custom".to_string(),
+        build => build.replace(['-', '+'], "_"),
+    };
+    if label == "official" || !crate::version::is_valid_label(&label) {
+        DEFAULT_LABEL.to_string()
+    } else {
+        label
+    }
+}
+
+/// Resolves what `path` points at to the executable, or the `.app` bundle on macOS.
+/// Directories are searched the way installed versions are.
+pub fn find_target(path: &Path) -> Result<PathBuf> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("{} does not exist", path.display()))?;
+    if path.extension().is_some_and(|e| e == "app") || path.is_file() {
+        return Ok(path);
+    }
+    crate::commands::find_godot_executable(&path)
+}
+
+/// The executable inside a `.app` bundle, or `target` itself
+pub fn executable_of(target: &Path) -> PathBuf {
+    if target.extension().is_some_and(|e| e == "app") {
+        target.join("Contents").join("MacOS").join("Godot")
+    } else {
+        target.to_path_buf()
+    }
+}
+
+/// Creates `version_dir` holding a link to `target`, named so that it is found like the
+/// executable of an installed version.
+pub fn create(version_dir: &Path, target: &Path) -> Result<()> {
+    let name = if target.extension().is_some_and(|e| e == "app") {
+        LINK_NAMES[1]
+    } else {
+        LINK_NAMES[0]
+    };
+    if version_dir.exists() {
+        bail!("{} already exists", version_dir.display());
+    }
+    std::fs::create_dir_all(version_dir).context("Failed to create engine directory")?;
+
+    let link = version_dir.join(name);
+    #[cfg(unix)]
+    let result = std::os::unix::fs::symlink(target, &link);
+    #[cfg(windows)]
+    let result = if target.is_dir() {
+        std::os::windows::fs::symlink_dir(target, &link)
+    } else {
+        std::os::windows::fs::symlink_file(target, &link)
+    };
+    if let Err(e) = result {
+        let _ = std::fs::remove_dir_all(version_dir);
+        return Err(e).context(if cfg!(windows) {
+            "Failed to create link. Creating symlinks needs Developer Mode on Windows"
+        } else {
+            "Failed to create link"
+        });
+    }
+    Ok(())
+}
+
+/// Where a linked engine lives, `None` for versions installed by godo
+pub fn target(version_dir: &Path) -> Option<PathBuf> {
+    LINK_NAMES.iter().find_map(|name| {
+        let link = version_dir.join(name);
+        link.symlink_metadata()
+            .ok()
+            .filter(|m| m.file_type().is_symlink())
+            .and_then(|_| std::fs::read_link(&link).ok())
+    })
+}
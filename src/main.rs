@@ -1,15 +1,48 @@
 mod commands;
 mod config;
+mod export;
 mod github;
+mod http;
+mod import;
+mod link;
+mod matrix;
+mod process;
+mod project;
+mod prompt;
+mod settings;
+mod snapshot;
+mod source;
+#[cfg(test)]
+mod testing;
+mod trash;
 mod version;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use config::AutoSetCurrent;
+use import::ImportMode;
+use version::Channel;
 
 #[derive(Parser)]
 #[command(name = "godo")]
 #[command(about = "A version manager for Godot Engine", version)]
 struct Cli {
+    /// Never access the network, use only the cached manifest and archives
+    #[arg(long, global = true, env = "GODO_OFFLINE", value_parser = clap::builder::FalseyValueParser::new())]
+    offline: bool,
+
+    /// Answer yes to every confirmation
+    #[arg(long, short = 'y', global = true)]
+    yes: bool,
+
+    /// Never prompt, fail instead when a question has no configured answer.
+    /// Implied when stdin is not a terminal
+    #[arg(long, global = true)]
+    no_input: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -18,8 +51,9 @@ struct Cli {
 enum Commands {
     /// Install Godot Engine with specific version
     Install {
-        /// The version to install. Fuzzy matching is supported.
-        version: String,
+        /// The versions to install. Fuzzy matching is supported.
+        #[arg(required = true)]
+        version: Vec<String>,
 
         /// Whether to install Mono version
         #[arg(long, num_args = 0..=1, default_missing_value = "true")]
@@ -28,12 +62,63 @@ enum Commands {
         /// Install directly without interactive interface, works only when --mono is specified
         #[arg(long)]
         silent: bool,
+
+        /// Install from a local archive instead of downloading. Requires a full version, e.g. 4.3-stable
+        #[arg(long, value_name = "ZIP")]
+        from_file: Option<PathBuf>,
+
+        /// Whether to set the installed version as current. Overrides auto_set_current
+        #[arg(long, value_enum)]
+        set_current: Option<AutoSetCurrent>,
+
+        /// Least stable release to pick when no pre-release is given. Overrides default_channel
+        #[arg(long, value_enum)]
+        channel: Option<Channel>,
+
+        /// Keep the editor settings of this install separate from other versions
+        #[arg(long)]
+        self_contained: bool,
+
+        /// Install even when the official checksums cannot be downloaded
+        #[arg(long)]
+        skip_checksum: bool,
+    },
+
+    /// Register an engine installed elsewhere, e.g. a custom build or the Steam version
+    Link {
+        /// The executable, its directory, or Godot.app on macOS
+        path: PathBuf,
+
+        /// Label such as `mybuild`, or a full name such as `4.4-dev3+mybuild`.
+        /// Defaults to the build name the engine reports
+        #[arg(long = "as", value_name = "NAME")]
+        name: Option<String>,
+
+        /// Whether to set the linked version as current. Overrides auto_set_current
+        #[arg(long, value_enum)]
+        set_current: Option<AutoSetCurrent>,
+    },
+
+    /// Find engines installed by other tools or by hand and bring them under godo
+    Import {
+        /// Directories to scan instead of the usual locations (Steam, ~/Applications, /opt,
+        /// godotenv, gdvm and Godots)
+        path: Vec<PathBuf>,
+
+        /// Link, copy or move every engine found instead of asking
+        #[arg(long, value_enum)]
+        mode: Option<ImportMode>,
+
+        /// Only show what would be imported
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Remove a locally installed Godot version
     Rm {
-        /// The version to remove. Fuzzy matching is supported.
-        version: String,
+        /// The versions to remove. Fuzzy matching is supported.
+        #[arg(required = true)]
+        version: Vec<String>,
 
         /// Whether to remove Mono version
         #[arg(long, num_args = 0..=1, default_missing_value = "true")]
@@ -42,13 +127,63 @@ enum Commands {
         /// Remove directly without interactive interface, works only when --mono is specified
         #[arg(long)]
         silent: bool,
+
+        /// Take the newest match instead of asking when the version is ambiguous
+        #[arg(long)]
+        latest: bool,
+    },
+
+    /// Remove every installed version except the current one, project pins and the newest per minor
+    Prune {
+        /// How many of the newest installs to keep per minor version
+        #[arg(long, default_value_t = 1)]
+        keep: usize,
+
+        /// Only show what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Copy, back up and restore editor settings
+    Settings {
+        #[command(subcommand)]
+        action: SettingsAction,
+    },
+
+    /// Restore a removed version from the trash
+    Restore {
+        /// The version to restore. Fuzzy matching is supported.
+        version: String,
+    },
+
+    /// Inspect or empty the trash of removed versions
+    Trash {
+        #[command(subcommand)]
+        action: TrashAction,
     },
 
     /// List all available Godot versions
     List {
-        /// Show pre-release versions (beta, rc, dev, alpha)
+        /// Show pre-release versions (beta, rc, dev, alpha). Overrides include_prereleases_in_list
+        #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+        beta: Option<bool>,
+
+        /// Only list installed versions, marking self-contained ones
         #[arg(long)]
-        beta: bool,
+        installed: bool,
+    },
+
+    /// Turn self-contained mode of an install on or off
+    Sc {
+        #[arg(value_enum)]
+        mode: Switch,
+
+        /// The version to change. Fuzzy matching is supported.
+        version: String,
+
+        /// Whether to change the Mono version
+        #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+        mono: Option<bool>,
     },
 
     /// Set the current active Godot version
@@ -63,6 +198,10 @@ enum Commands {
         /// Select directly without interactive interface, works only when --mono is specified
         #[arg(long)]
         silent: bool,
+
+        /// Take the newest match instead of asking when the version is ambiguous
+        #[arg(long)]
+        latest: bool,
     },
 
     /// Launch a Godot Engine instance
@@ -73,42 +212,506 @@ enum Commands {
         /// Whether to launch Mono version
         #[arg(long, num_args = 0..=1, default_missing_value = "true")]
         mono: Option<bool>,
+
+        /// Take the newest match instead of asking when the version is ambiguous
+        #[arg(long)]
+        latest: bool,
+
+        /// Write the output of the engine to a log. Overrides launch_logs
+        #[arg(long, overrides_with = "no_log")]
+        log: bool,
+
+        /// Discard the output of the engine. Overrides launch_logs
+        #[arg(long)]
+        no_log: bool,
+
+        /// Open this project in the editor. Refuses engines that would upgrade or
+        /// downgrade it, unless allowed
+        #[arg(long, value_name = "DIR")]
+        project: Option<PathBuf>,
+
+        /// Allow opening the project in a newer minor or major version, which upgrades it
+        #[arg(long)]
+        allow_upgrade: bool,
+
+        /// Snapshot the project metadata before a mismatched engine opens it, without asking
+        #[arg(long)]
+        snapshot: bool,
+    },
+
+    /// Show the output of the latest launch
+    Logs {
+        /// Show the latest launch of this version. Fuzzy matching is supported.
+        version: Option<String>,
+
+        /// Keep printing new output until the engine exits
+        #[arg(long, short = 'f')]
+        follow: bool,
+    },
+
+    /// Runs an engine and records its exit, used by `run`
+    #[command(hide = true)]
+    Watch {
+        version: String,
+        executable: PathBuf,
+        work_dir: PathBuf,
+        #[arg(long)]
+        log: Option<PathBuf>,
+        /// Arguments passed on to the engine
+        #[arg(last = true)]
+        args: Vec<OsString>,
+    },
+
+    /// List the projects of the Godot editor and the engine each one needs
+    Projects,
+
+    /// Open a project in the editor of the engine it needs
+    Open {
+        /// Name of a project in the project list of the editor, or its directory
+        project: String,
+
+        /// Allow opening the project in a newer minor or major version, which upgrades it
+        #[arg(long)]
+        allow_upgrade: bool,
+
+        /// Snapshot the project metadata before a mismatched engine opens it, without asking
+        #[arg(long)]
+        snapshot: bool,
+    },
+
+    /// Convert a Godot 3 project to Godot 4, on a copy of the project
+    Convert {
+        /// Name of a project in the project list of the editor, or its directory
+        project: String,
+
+        /// The Godot 4 version to convert with, e.g. 4.x or 4.3. Installed when missing
+        #[arg(long, default_value = "4.x")]
+        to: String,
+
+        /// Where to put the converted copy. Defaults to <project>-godot4 next to the project
+        #[arg(long, value_name = "DIR", conflicts_with = "in_place")]
+        out: Option<PathBuf>,
+
+        /// Convert the project itself instead of a copy
+        #[arg(long)]
+        in_place: bool,
+
+        /// Files larger than this many KiB are not converted
+        #[arg(long, default_value_t = 4096)]
+        max_file_kb: u64,
+
+        /// Lines longer than this are not converted
+        #[arg(long, default_value_t = 100000)]
+        max_line_size: u64,
+    },
+
+    /// Export a project with the engine it needs, installing export templates when missing
+    Export {
+        /// Name of the preset in export_presets.cfg
+        preset: String,
+
+        /// File to export to
+        output: PathBuf,
+
+        /// The project directory. Defaults to the working directory
+        #[arg(long, value_name = "DIR")]
+        project: Option<PathBuf>,
+
+        /// Export a debug build
+        #[arg(long)]
+        debug: bool,
+    },
+
+    /// Run the engine headless on a set of versions and report which ones pass
+    Matrix {
+        /// Versions to run, e.g. 4.1..4.4 or 4.2,4.3. The newest install of each minor
+        /// version is used
+        #[arg(long)]
+        versions: String,
+
+        /// Run the Mono builds
+        #[arg(long)]
+        mono: bool,
+
+        /// Install the newest release of minor versions that are not installed
+        #[arg(long)]
+        install: bool,
+
+        /// How many engines to run at once
+        #[arg(long, short = 'j', default_value_t = 1)]
+        jobs: usize,
+
+        /// Where to write the JUnit XML report
+        #[arg(long, value_name = "FILE", default_value = "godo-matrix.xml")]
+        report: PathBuf,
+
+        /// Arguments passed to every engine, e.g. -- --path . -s res://tests/run.gd
+        #[arg(last = true)]
+        args: Vec<OsString>,
+    },
+
+    /// Restore project metadata saved before opening it in another version
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// List running engines launched by godo
+    Ps,
+
+    /// Stop running engines of a version launched by godo
+    Kill {
+        /// The version to stop. Fuzzy matching is supported.
+        version: String,
     },
 
     /// Update the Godot Engine release manifest manually
     Update,
+
+    /// Install the newest patch of installed versions and move current along
+    Upgrade {
+        /// Only upgrade installs matching this version. Fuzzy matching is supported.
+        version: Option<String>,
+
+        /// Remove the old versions after upgrading
+        #[arg(long)]
+        remove_old: bool,
+    },
+
+    /// List installed versions that have a newer patch release
+    Outdated,
+
+    /// View or change the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Check the godo setup and GitHub API quota
+    Doctor {
+        /// Also test connectivity through the configured proxy, certificates and mirrors
+        #[arg(long)]
+        network: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Switch {
+    On,
+    Off,
+}
+
+#[derive(Subcommand)]
+enum SnapshotAction {
+    /// List snapshots, of one project or of all
+    List {
+        /// Name or directory of the project
+        project: Option<String>,
+    },
+
+    /// Restore the latest snapshot of a project, or the one given by --id
+    Restore {
+        /// Name or directory of the project. Defaults to the working directory
+        project: Option<String>,
+
+        #[arg(long)]
+        id: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SettingsAction {
+    /// Copy editor settings, templates and feature profiles from one install to another
+    Copy {
+        /// The version to copy from. Fuzzy matching is supported.
+        from: String,
+        /// The version to copy to. Fuzzy matching is supported.
+        to: String,
+    },
+
+    /// Snapshot the editor settings of an install
+    Backup {
+        /// Fuzzy matching is supported.
+        version: String,
+    },
+
+    /// Restore the latest snapshot of an install, or the one given by --id
+    Restore {
+        /// Fuzzy matching is supported.
+        version: String,
+
+        #[arg(long)]
+        id: Option<String>,
+    },
+
+    /// List settings snapshots
+    Backups,
+}
+
+#[derive(Subcommand)]
+enum TrashAction {
+    /// List removed versions
+    List,
+
+    /// Delete removed versions permanently
+    Empty {
+        /// Only delete versions removed longer ago than this, e.g. 7d or 12h
+        #[arg(long, value_name = "DURATION")]
+        older_than: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Show all settings
+    List,
+
+    /// Show a single setting
+    Get {
+        /// The key, e.g. engine_dir or network.proxy
+        key: String,
+    },
+
+    /// Change a setting. Durations like 3h or 1d are accepted for invalidate_time
+    Set {
+        /// The key, e.g. engine_dir or network.proxy
+        key: String,
+
+        /// The new value
+        value: String,
+    },
+
+    /// Remove a setting, falling back to its default
+    Unset {
+        /// The key, e.g. github_token
+        key: String,
+    },
+
+    /// Open the config file in $VISUAL or $EDITOR
+    Edit,
+
+    /// Print the path of the config file
+    Path,
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    let config = match config::Config::load() {
+    // The watcher runs detached from any terminal and needs no config
+    if let Commands::Watch {
+        version,
+        executable,
+        work_dir,
+        log,
+        args,
+    } = &cli.command
+    {
+        if process::watch(version, executable, work_dir, args, log.as_deref()).is_err() {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // The config command must work even when the config file is invalid
+    if let Commands::Config { action } = &cli.command {
+        let result = config::Config::migrate_legacy().and_then(|_| match action {
+            ConfigAction::List => commands::config_list(),
+            ConfigAction::Get { key } => commands::config_get(key),
+            ConfigAction::Set { key, value } => commands::config_set(key, value),
+            ConfigAction::Unset { key } => commands::config_unset(key),
+            ConfigAction::Edit => commands::config_edit(),
+            ConfigAction::Path => commands::config_path(),
+        });
+        if let Err(e) = result {
+            eprintln!("{} {e:#}", "!".red().bold());
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut config = match config::Config::load() {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("Error loading config: {e}");
+            eprintln!("Error loading config: {e:#}");
             std::process::exit(1);
         }
     };
+    config.offline = cli.offline;
+    config.prompter = prompt::Prompter::new(cli.yes, cli.no_input);
+
+    if let Err(e) = http::init(&config) {
+        eprintln!("{} {e:#}", "!".red().bold());
+        std::process::exit(1);
+    }
 
     let result = match cli.command {
         Commands::Install {
             version,
             mono,
             silent,
-        } => commands::install(&config, &version, mono, silent),
+            from_file,
+            set_current,
+            channel,
+            self_contained,
+            skip_checksum,
+        } => commands::install(
+            &config,
+            &version,
+            commands::InstallOptions {
+                mono,
+                silent,
+                from_file: from_file.as_deref(),
+                set_current,
+                channel,
+                self_contained,
+                skip_checksum,
+            },
+        ),
+        Commands::Link {
+            path,
+            name,
+            set_current,
+        } => commands::link(&config, &path, name.as_deref(), set_current),
+        Commands::Import {
+            path,
+            mode,
+            dry_run,
+        } => commands::import(&config, &path, mode, dry_run),
         Commands::Rm {
             version,
             mono,
             silent,
-        } => commands::rm(&config, &version, mono, silent),
-        Commands::List { beta } => commands::list(&config, beta),
+            latest,
+        } => commands::rm(&config, &version, mono, silent, latest),
+        Commands::Prune { keep, dry_run } => commands::prune(&config, keep, dry_run),
+        Commands::Settings { action } => match action {
+            SettingsAction::Copy { from, to } => commands::settings_copy(&config, &from, &to),
+            SettingsAction::Backup { version } => commands::settings_backup(&config, &version),
+            SettingsAction::Restore { version, id } => {
+                commands::settings_restore(&config, &version, id.as_deref())
+            }
+            SettingsAction::Backups => commands::settings_backups(),
+        },
+        Commands::Restore { version } => commands::restore(&config, &version),
+        Commands::Trash { action } => match action {
+            TrashAction::List => commands::trash_list(&config),
+            TrashAction::Empty { older_than } => {
+                commands::trash_empty(&config, older_than.as_deref())
+            }
+        },
+        Commands::List { beta, installed } => commands::list(&config, beta, installed),
+        Commands::Sc {
+            mode,
+            version,
+            mono,
+        } => commands::sc(&config, matches!(mode, Switch::On), &version, mono),
         Commands::Current {
             version,
             mono,
             silent,
-        } => commands::current(&config, &version, mono, silent),
-        Commands::Run { version, mono } => commands::run(&config, version.as_deref(), mono),
+            latest,
+        } => commands::current(&config, &version, mono, silent, latest),
+        Commands::Run {
+            version,
+            mono,
+            latest,
+            log,
+            no_log,
+            project,
+            allow_upgrade,
+            snapshot,
+        } => {
+            let log = match (log, no_log) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            commands::run(
+                &config,
+                version.as_deref(),
+                commands::RunOptions {
+                    mono,
+                    latest,
+                    log,
+                    project: project.as_deref(),
+                    allow_upgrade,
+                    snapshot,
+                },
+            )
+        }
+        Commands::Logs { version, follow } => commands::logs(version.as_deref(), follow),
+        Commands::Ps => commands::ps(),
+        Commands::Kill { version } => commands::kill(&version),
         Commands::Update => commands::update(&config),
+        Commands::Upgrade {
+            version,
+            remove_old,
+        } => commands::upgrade(&config, version.as_deref(), remove_old),
+        Commands::Projects => commands::projects(&config),
+        Commands::Open {
+            project,
+            allow_upgrade,
+            snapshot,
+        } => commands::open(&config, &project, allow_upgrade, snapshot),
+        Commands::Convert {
+            project,
+            to,
+            out,
+            in_place,
+            max_file_kb,
+            max_line_size,
+        } => commands::convert(
+            &config,
+            &project,
+            commands::ConvertOptions {
+                to: &to,
+                out: out.as_deref(),
+                in_place,
+                max_file_kb,
+                max_line_size,
+            },
+        ),
+        Commands::Export {
+            preset,
+            output,
+            project,
+            debug,
+        } => commands::export(&config, &preset, &output, project.as_deref(), debug).map(|code| {
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }),
+        Commands::Matrix {
+            versions,
+            mono,
+            install,
+            jobs,
+            report,
+            args,
+        } => commands::matrix(
+            &config,
+            commands::MatrixOptions {
+                versions: &versions,
+                mono,
+                install,
+                jobs,
+                report: &report,
+                args: &args,
+            },
+        ),
+        Commands::Snapshot { action } => match action {
+            SnapshotAction::List { project } => {
+                commands::snapshot_list(&config, project.as_deref())
+            }
+            SnapshotAction::Restore { project, id } => {
+                commands::snapshot_restore(&config, project.as_deref(), id.as_deref())
+            }
+        },
+        Commands::Outdated => commands::outdated(&config),
+        Commands::Doctor { network } => commands::doctor(&config, network),
+        Commands::Config { .. } | Commands::Watch { .. } => {
+            unreachable!("handled before loading the config")
+        }
     };
 
     if let Err(e) = result {
@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use crate::process::ExitInfo;
+use crate::version::GodotVersion;
+
+/// Outcome of running the engine of one version
+#[derive(Debug)]
+pub enum Outcome {
+    Ran {
+        exit: ExitInfo,
+        duration: Duration,
+        output: String,
+    },
+    /// The engine could not be installed or started, with the reason
+    Error(String),
+}
+
+impl Outcome {
+    pub fn passed(&self) -> bool {
+        matches!(self, Outcome::Ran { exit, .. } if !exit.crashed())
+    }
+
+    pub fn failed(&self) -> bool {
+        !self.passed()
+    }
+}
+
+/// A JUnit XML report with one test case per version, readable by most CI systems
+pub fn junit(results: &[(GodotVersion, Outcome)]) -> String {
+    let tests = results.len();
+    let failures = results.iter().filter(|(_, o)| o.failed()).count();
+    let errors = results
+        .iter()
+        .filter(|(_, o)| matches!(o, Outcome::Error(_)))
+        .count();
+    let failures = failures - errors;
+    let time: f64 = results
+        .iter()
+        .map(|(_, o)| match o {
+            Outcome::Ran { duration, .. } => duration.as_secs_f64(),
+            Outcome::Error(_) => 0.0,
+        })
+        .sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"godo matrix\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">\n"
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"godo matrix\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">\n"
+    ));
+    for (version, outcome) in results {
+        let name = escape(&version.to_string());
+        match outcome {
+            Outcome::Ran {
+                exit,
+                duration,
+                output,
+            } => {
+                xml.push_str(&format!(
+                    "    <testcase classname=\"godot\" name=\"{name}\" time=\"{:.3}\">\n",
+                    duration.as_secs_f64()
+                ));
+                if exit.crashed() {
+                    xml.push_str(&format!(
+                        "      <failure message=\"Godot {}\"/>\n",
+                        escape(&exit.describe())
+                    ));
+                }
+                xml.push_str(&format!(
+                    "      <system-out>{}</system-out>\n",
+                    escape(output)
+                ));
+                xml.push_str("    </testcase>\n");
+            }
+            Outcome::Error(reason) => {
+                xml.push_str(&format!(
+                    "    <testcase classname=\"godot\" name=\"{name}\" time=\"0\">\n"
+                ));
+                xml.push_str(&format!("      <error message=\"{}\"/>\n", escape(reason)));
+                xml.push_str("    </testcase>\n");
+            }
+        }
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Escapes text for XML. Control characters such as the escapes of coloured output are
+/// not allowed in XML 1.0 and are dropped.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ran(code: i32, output: &str) -> Outcome {
+        Outcome::Ran {
+            exit: ExitInfo {
+                at: 0,
+                code: Some(code),
+                signal: None,
+            },
+            duration: Duration::from_millis(1500),
+            output: output.to_string(),
+        }
+    }
+
+    fn version(folder: &str) -> GodotVersion {
+        GodotVersion::from_folder(folder).unwrap()
+    }
+
+    #[test]
+    fn escape_quotes_markup_and_drops_control_characters() {
+        assert_eq!(
+            escape("a<b> & \"c\" 'd'"),
+            "a&lt;b&gt; &amp; &quot;c&quot; &apos;d&apos;"
+        );
+        assert_eq!(escape("\u{1b}[31mred\u{1b}[0m\tok\n"), "[31mred[0m\tok\n");
+    }
+
+    #[test]
+    fn junit_counts_failures_and_errors_apart() {
+        let results = vec![
+            (version("4.2.2-stable"), ran(0, "fine")),
+            (version("4.3.0-stable"), ran(1, "<boom>")),
+            (
+                version("4.4.0-stable"),
+                Outcome::Error("Failed to install".to_string()),
+            ),
+        ];
+        let xml = junit(&results);
+        assert!(xml.contains(
+            "<testsuite name=\"godo matrix\" tests=\"3\" failures=\"1\" errors=\"1\" time=\"3.000\">"
+        ));
+        assert!(xml.contains("<failure message=\"Godot exited with code 1\"/>"));
+        assert!(xml.contains("<system-out>&lt;boom&gt;</system-out>"));
+        assert!(xml.contains("<error message=\"Failed to install\"/>"));
+        assert_eq!(xml.matches("<testcase ").count(), 3);
+        assert_eq!(xml.matches("<failure ").count(), 1);
+    }
+
+    #[test]
+    fn junit_of_no_results_is_an_empty_suite() {
+        let xml = junit(&[]);
+        assert!(xml.contains("tests=\"0\" failures=\"0\" errors=\"0\""));
+        assert!(!xml.contains("<testcase"));
+    }
+}
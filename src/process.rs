@@ -0,0 +1,509 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+
+use crate::config::Config;
+use crate::trash::now;
+use crate::version::GodotVersion;
+
+/// Logs kept per version, older ones are deleted on launch
+const KEEP_LOGS: usize = 10;
+/// Finished launches kept in the state file for `godo ps`
+const KEEP_FINISHED: usize = 20;
+
+/// An engine process started by `godo run`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Launch {
+    pub pid: u32,
+    /// Folder name of the install, see `GodotVersion::folder_name`
+    pub version: String,
+    pub executable: PathBuf,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<PathBuf>,
+    /// PID of the `godo watch` process waiting for the engine
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watcher: Option<u32>,
+    /// Set by the watcher once the engine exited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit: Option<ExitInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitInfo {
+    pub at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<i32>,
+    /// Signal that killed the process, Unix only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+}
+
+impl ExitInfo {
+    fn from_status(status: &ExitStatus) -> Self {
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(status);
+        #[cfg(not(unix))]
+        let signal = None;
+        ExitInfo {
+            at: now(),
+            code: status.code(),
+            signal,
+        }
+    }
+
+    pub fn crashed(&self) -> bool {
+        self.signal.is_some() || self.code.is_some_and(|c| c != 0)
+    }
+
+    pub fn describe(&self) -> String {
+        match (self.code, self.signal) {
+            (_, Some(signal)) => format!("killed by signal {signal}"),
+            (Some(code), None) => format!("exited with code {code}"),
+            (None, None) => "exited".to_string(),
+        }
+    }
+}
+
+impl Launch {
+    pub fn version(&self) -> Option<GodotVersion> {
+        GodotVersion::from_folder(&self.version)
+    }
+
+    /// Whether the process still exists and is still this engine, not a reused PID.
+    pub fn is_alive(&self) -> bool {
+        self.exit.is_none() && is_alive(self.pid) && runs_executable(self.pid, &self.executable)
+    }
+
+    /// Whether the exit may still be recorded: the engine runs, or its watcher is still about
+    /// to write down how it ended.
+    fn is_pending(&self) -> bool {
+        if self.is_alive() {
+            return true;
+        }
+        let godo = std::env::current_exe().and_then(std::fs::canonicalize);
+        self.watcher.is_some_and(|watcher| {
+            is_alive(watcher) && godo.is_ok_and(|godo| runs_executable(watcher, &godo))
+        })
+    }
+}
+
+fn state_path() -> PathBuf {
+    Config::cache_dir().join("running.json")
+}
+
+fn load() -> Vec<Launch> {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Changes the state file while holding a lock on it, so concurrent launches and watchers
+/// don't drop each other's entries. The new content replaces the file in one rename, so
+/// `load` never sees half of it.
+fn update(change: impl FnOnce(&mut Vec<Launch>)) -> Result<()> {
+    let path = state_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let lock = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("lock"))
+        .context("Failed to open process list lock")?;
+    lock.lock().context("Failed to lock process list")?;
+
+    let mut launches = load();
+    change(&mut launches);
+    let content = serde_json::to_string(&launches).context("Failed to serialize process list")?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, content).context("Failed to write process list")?;
+    std::fs::rename(&temp, &path).context("Failed to write process list")
+}
+
+/// Drops processes that vanished without a recorded exit and all but the latest
+/// finished launches.
+fn prune(launches: &mut Vec<Launch>) {
+    let (finished, unfinished): (Vec<Launch>, Vec<Launch>) = std::mem::take(launches)
+        .into_iter()
+        .partition(|l| l.exit.is_some());
+    let skip = finished.len().saturating_sub(KEEP_FINISHED);
+    launches.extend(finished.into_iter().skip(skip));
+    launches.extend(unfinished.into_iter().filter(Launch::is_pending));
+}
+
+/// Loads the state file, pruning it when it holds launches that are no longer needed.
+fn load_pruned() -> Vec<Launch> {
+    let mut launches = load();
+    let before = launches.len();
+    prune(&mut launches);
+    if launches.len() != before {
+        let _ = update(prune);
+    }
+    launches
+}
+
+/// Records a launched engine so `rm` and friends can tell it is in use.
+fn record(pid: u32, version: &str, executable: &Path, log: Option<&Path>) -> Result<()> {
+    let launch = Launch {
+        pid,
+        version: version.to_string(),
+        executable: std::fs::canonicalize(executable).unwrap_or_else(|_| executable.to_path_buf()),
+        started_at: now(),
+        log: log.map(Path::to_path_buf),
+        watcher: Some(std::process::id()),
+        exit: None,
+    };
+    update(|launches| {
+        prune(launches);
+        launches.push(launch);
+    })
+}
+
+fn record_exit(pid: u32, exit: ExitInfo) -> Result<()> {
+    update(|launches| {
+        if let Some(launch) = launches
+            .iter_mut()
+            .rev()
+            .find(|l| l.pid == pid && l.exit.is_none())
+        {
+            launch.exit = Some(exit);
+        }
+    })
+}
+
+/// Engines launched by godo that are still running.
+pub fn running() -> Vec<Launch> {
+    load_pruned().into_iter().filter(Launch::is_alive).collect()
+}
+
+/// Launches whose engine has exited, latest first
+pub fn finished() -> Vec<Launch> {
+    let mut finished: Vec<Launch> = load_pruned()
+        .into_iter()
+        .filter(|l| l.exit.is_some())
+        .collect();
+    finished.sort_by_key(|l| std::cmp::Reverse(l.started_at));
+    finished
+}
+
+/// Running engines of the given install
+pub fn running_version(version: &GodotVersion) -> Vec<Launch> {
+    let folder = version.folder_name();
+    running()
+        .into_iter()
+        .filter(|l| l.version == folder)
+        .collect()
+}
+
+/// Latest launch that wrote to `log`
+pub fn launch_of_log(log: &Path) -> Option<Launch> {
+    load()
+        .into_iter()
+        .rev()
+        .find(|l| l.log.as_deref() == Some(log))
+}
+
+pub fn logs_dir() -> PathBuf {
+    Config::data_dir().join("logs")
+}
+
+/// Log files of one install, oldest first
+pub fn logs_of(folder: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(logs_dir().join(folder)) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "log"))
+        .collect();
+    logs.sort_by_key(|l| log_order(l));
+    logs
+}
+
+/// Sort key of a log named `{stamp}.log` or, for a second launch within the same second,
+/// `{stamp}-{n}.log`. Plain name order would put `-2` before the first log.
+pub fn log_order(log: &Path) -> (String, u32) {
+    let stem = log
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stamp_len = "YYYYMMDD-HHMMSS".len();
+    let n = stem
+        .get(stamp_len..)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .and_then(|n| n.parse().ok());
+    match n {
+        Some(n) => (stem[..stamp_len].to_string(), n),
+        None => (stem, 1),
+    }
+}
+
+/// Creates the path of a new log for `version` and deletes all but the newest logs.
+fn new_log(version: &GodotVersion) -> Result<PathBuf> {
+    let folder = version.folder_name();
+    let dir = logs_dir().join(&folder);
+    std::fs::create_dir_all(&dir).context("Failed to create log directory")?;
+
+    let logs = logs_of(&folder);
+    let excess = (logs.len() + 1).saturating_sub(KEEP_LOGS);
+    for old in &logs[..excess] {
+        let _ = std::fs::remove_file(old);
+    }
+
+    let stamp = timestamp(now());
+    let mut path = dir.join(format!("{stamp}.log"));
+    let mut n = 1;
+    while path.exists() {
+        n += 1;
+        path = dir.join(format!("{stamp}-{n}.log"));
+    }
+    Ok(path)
+}
+
+/// Starts the engine through a detached watcher process, which records its PID and exit status.
+/// Returns the log file when logging is enabled.
+pub fn launch(
+    version: &GodotVersion,
+    executable: &Path,
+    work_dir: &Path,
+    args: &[OsString],
+    log: bool,
+) -> Result<Option<PathBuf>> {
+    let log = if log { Some(new_log(version)?) } else { None };
+
+    let mut watcher = Command::new(std::env::current_exe().context("Cannot locate godo")?);
+    watcher
+        .arg("watch")
+        .arg(version.folder_name())
+        .arg(executable)
+        .arg(work_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(log) = &log {
+        watcher.arg("--log").arg(log);
+    }
+    watcher.arg("--").args(args);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        watcher.process_group(0);
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+        const DETACHED_PROCESS: u32 = 0x00000008;
+        watcher.creation_flags(CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS);
+    }
+
+    let started = now();
+    let mut watcher = watcher.spawn().context("Failed to launch Godot")?;
+
+    // Wait until the watcher recorded the engine, so it is protected right away
+    let folder = version.folder_name();
+    for _ in 0..50 {
+        if load()
+            .iter()
+            .any(|l| l.version == folder && l.started_at >= started)
+        {
+            break;
+        }
+        if let Some(status) = watcher.try_wait()? {
+            if !status.success() {
+                bail!("Failed to launch Godot");
+            }
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    Ok(log)
+}
+
+/// Body of the hidden `godo watch` command: runs the engine and waits for it to exit.
+pub fn watch(
+    folder: &str,
+    executable: &Path,
+    work_dir: &Path,
+    args: &[OsString],
+    log: Option<&Path>,
+) -> Result<()> {
+    let (stdout, stderr) = match log {
+        Some(log) => {
+            let file = std::fs::File::create(log).context("Failed to create log file")?;
+            (Stdio::from(file.try_clone()?), Stdio::from(file))
+        }
+        None => (Stdio::null(), Stdio::null()),
+    };
+
+    let mut child = Command::new(executable)
+        .args(args)
+        .current_dir(work_dir)
+        .stdin(Stdio::null())
+        .stdout(stdout)
+        .stderr(stderr)
+        .spawn()
+        .context("Failed to launch Godot")?;
+    record(child.id(), folder, executable, log)?;
+
+    let status = child.wait().context("Failed to wait for Godot")?;
+    let exit = ExitInfo::from_status(&status);
+    if let Some(log) = log {
+        if let Ok(mut file) = std::fs::OpenOptions::new().append(true).open(log) {
+            let _ = writeln!(file, "\n[godo] Godot {}", exit.describe());
+        }
+    }
+    record_exit(child.id(), exit)
+}
+
+/// Runs the engine in the foreground and waits for it, writing its stdout and stderr
+/// interleaved to `output`.
+pub fn capture(
+    executable: &Path,
+    work_dir: &Path,
+    args: &[OsString],
+    output: &Path,
+) -> Result<ExitInfo> {
+    let file = std::fs::File::create(output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+    let status = Command::new(executable)
+        .args(args)
+        .current_dir(work_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(file.try_clone()?))
+        .stderr(Stdio::from(file))
+        .status()
+        .context("Failed to run Godot")?;
+    Ok(ExitInfo::from_status(&status))
+}
+
+/// Runs the engine in the foreground with the terminal attached and waits for it.
+pub fn exec(executable: &Path, work_dir: &Path, args: &[OsString]) -> Result<ExitInfo> {
+    let status = Command::new(executable)
+        .args(args)
+        .current_dir(work_dir)
+        .status()
+        .context("Failed to run Godot")?;
+    Ok(ExitInfo::from_status(&status))
+}
+
+/// Asks the process to exit, like closing its window.
+pub fn terminate(pid: u32) -> Result<()> {
+    #[cfg(unix)]
+    {
+        let pid = libc::pid_t::try_from(pid).context("Invalid PID")?;
+        // SAFETY: kill has no memory safety requirements
+        if unsafe { libc::kill(pid, libc::SIGTERM) } != 0 {
+            bail!(
+                "Failed to stop process {pid}: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        let status = Command::new("taskkill")
+            .args(["/PID", &pid.to_string()])
+            .stdout(Stdio::null())
+            .status()
+            .context("Failed to run taskkill")?;
+        if !status.success() {
+            bail!("Failed to stop process {pid}");
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    bail!("Stopping process {pid} is not supported on this platform");
+
+    #[cfg(any(unix, windows))]
+    Ok(())
+}
+
+fn is_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        let Ok(pid) = libc::pid_t::try_from(pid) else {
+            return false;
+        };
+        // SAFETY: signal 0 only checks that the process exists
+        if unsafe { libc::kill(pid, 0) } == 0 {
+            return true;
+        }
+        // The process exists but belongs to someone else
+        std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+
+    #[cfg(windows)]
+    {
+        Command::new("tasklist")
+            .args(["/NH", "/FI", &format!("PID eq {pid}")])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).contains(&pid.to_string()))
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = pid;
+        false
+    }
+}
+
+/// Guards against PID reuse where the system tells which executable a process runs.
+/// Exited processes that were not reaped yet do not count.
+fn runs_executable(pid: u32, executable: &Path) -> bool {
+    if cfg!(target_os = "linux") {
+        let zombie = std::fs::read_to_string(format!("/proc/{pid}/stat"))
+            .ok()
+            .and_then(|stat| {
+                stat.rsplit_once(')')
+                    .map(|(_, rest)| rest.trim_start().starts_with('Z'))
+            })
+            .unwrap_or(false);
+        if zombie {
+            return false;
+        }
+        match std::fs::read_link(format!("/proc/{pid}/exe")) {
+            Ok(exe) => exe == executable,
+            // Not readable, e.g. another user's process
+            Err(_) => true,
+        }
+    } else {
+        true
+    }
+}
+
+/// UTC time as `20261019-043102`, used for log file names.
+fn timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // Civil date from days since 1970-01-01, after Howard Hinnant's algorithm
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}{month:02}{day:02}-{:02}{:02}{:02}",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
@@ -0,0 +1,236 @@
+use std::path::{Path, PathBuf};
+
+use crate::version::VersionQuery;
+
+/// File in a project directory naming the engine version it is pinned to, e.g. `4.3.1-stable`
+/// or `4.2-mono`
+pub const PIN_FILE: &str = ".godot-version";
+
+/// Name of the directory Godot keeps its editor data and settings in
+#[cfg(target_os = "linux")]
+const GODOT_DIR: &str = "godot";
+#[cfg(not(target_os = "linux"))]
+const GODOT_DIR: &str = "Godot";
+
+/// Where the Godot editor keeps `projects.cfg`, templates and other data
+pub fn editor_data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join(GODOT_DIR))
+}
+
+/// Where the Godot editor keeps `editor_settings-*.tres`
+pub fn editor_config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join(GODOT_DIR))
+}
+
+/// Projects registered in the project manager of Godot 4 (`projects.cfg`) and Godot 3
+/// (`editor_settings-3.tres`). Projects whose directory is gone are skipped.
+pub fn known_projects() -> Vec<PathBuf> {
+    let mut projects = Vec::new();
+
+    if let Some(content) =
+        editor_data_dir().and_then(|d| std::fs::read_to_string(d.join("projects.cfg")).ok())
+    {
+        for line in content.lines() {
+            if let Some(path) = line
+                .trim()
+                .strip_prefix('[')
+                .and_then(|l| l.strip_suffix(']'))
+            {
+                projects.push(PathBuf::from(path));
+            }
+        }
+    }
+
+    if let Some(content) = editor_config_dir()
+        .and_then(|d| std::fs::read_to_string(d.join("editor_settings-3.tres")).ok())
+    {
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            if key.trim().starts_with("projects/") {
+                projects.push(PathBuf::from(value.trim().trim_matches('"')));
+            }
+        }
+    }
+
+    projects.retain(|p| p.join("project.godot").is_file());
+    projects.sort();
+    projects.dedup();
+    projects
+}
+
+/// Reads the version pinned by the project's `.godot-version` file, if any.
+pub fn read_pin(project: &Path) -> Option<VersionQuery> {
+    let content = std::fs::read_to_string(project.join(PIN_FILE)).ok()?;
+    VersionQuery::from_input(content.lines().next()?)
+}
+
+/// A Godot project and the engine it asks for
+#[derive(Debug)]
+pub struct Project {
+    pub path: PathBuf,
+    /// `config/name`, or the directory name when it has none
+    pub name: String,
+    /// `(major, minor)` from `config/features`. Godot 3 projects have no features, their
+    /// major version comes from `config_version` and the minor is unknown
+    pub feature: Option<(u32, Option<u32>)>,
+    /// Whether the project uses C# and needs a Mono build
+    pub mono: bool,
+    pub pin: Option<VersionQuery>,
+}
+
+impl Project {
+    /// Reads `project.godot` in `path`.
+    pub fn read(path: &Path) -> Option<Project> {
+        let content = std::fs::read_to_string(path.join("project.godot")).ok()?;
+        let mut name = None;
+        let mut feature = None;
+        let mut config_version = None;
+        let mut mono = false;
+        let mut section = String::new();
+        for line in content.lines().map(str::trim) {
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = header.to_string();
+                mono |= section == "dotnet" || section == "mono";
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match (section.as_str(), key.trim()) {
+                ("", "config_version") => config_version = value.trim().parse::<u32>().ok(),
+                ("application", "config/name") => {
+                    name = Some(value.trim().trim_matches('"').to_string());
+                }
+                ("application", "config/features") => {
+                    let features: Vec<&str> = value.split('"').skip(1).step_by(2).collect();
+                    mono |= features.contains(&"C#");
+                    feature = features.iter().find_map(|f| {
+                        let (major, minor) = f.split_once('.')?;
+                        Some((major.parse().ok()?, Some(minor.parse().ok()?)))
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // config_version 5 is Godot 4, 3 and 4 are Godot 3
+        let feature = feature.or(match config_version {
+            Some(3 | 4) => Some((3, None)),
+            Some(5) => Some((4, None)),
+            _ => None,
+        });
+        let name = name.filter(|n| !n.is_empty()).unwrap_or_else(|| {
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string())
+        });
+        Some(Project {
+            path: path.to_path_buf(),
+            name,
+            feature,
+            mono,
+            pin: read_pin(path),
+        })
+    }
+
+    /// The versions able to open the project: its pin, or else its feature version
+    pub fn required(&self) -> Option<VersionQuery> {
+        if let Some(pin) = &self.pin {
+            return Some(VersionQuery {
+                mono: pin.mono.or(self.mono.then_some(true)),
+                ..pin.clone()
+            });
+        }
+        let (major, minor) = self.feature?;
+        Some(VersionQuery {
+            major,
+            minor,
+            patch: None,
+            pre: None,
+            mono: self.mono.then_some(true),
+            source: None,
+            label: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+    use crate::version::PreRelease;
+
+    /// Reads a project made of `project.godot` and an optional pin file
+    fn project(godot: &str, pin: Option<&str>) -> Option<Project> {
+        let dir = TempDir::new();
+        dir.write("project.godot", godot);
+        if let Some(pin) = pin {
+            dir.write(PIN_FILE, pin);
+        }
+        Project::read(dir.path())
+    }
+
+    #[test]
+    fn reads_name_feature_and_csharp() {
+        let godot = r#"config_version=5
+
+[application]
+
+config/name="Space Game"
+config/features=PackedStringArray("4.3", "C#", "Forward Plus")
+
+[dotnet]
+
+project/assembly_name="Space Game"
+"#;
+        let project = project(godot, None).unwrap();
+        assert_eq!(project.name, "Space Game");
+        assert_eq!(project.feature, Some((4, Some(3))));
+        assert!(project.mono);
+        let required = project.required().unwrap();
+        assert_eq!((required.major, required.minor), (4, Some(3)));
+        assert_eq!(required.mono, Some(true));
+    }
+
+    #[test]
+    fn godot3_projects_have_no_minor() {
+        let godot = "config_version=4\n\n[application]\n\nconfig/name=\"Old\"\n";
+        let project = project(godot, None).unwrap();
+        assert_eq!(project.feature, Some((3, None)));
+        assert!(!project.mono);
+    }
+
+    #[test]
+    fn unnamed_project_takes_the_directory_name() {
+        let project = project("config_version=5\n", None).unwrap();
+        assert!(project.name.starts_with("godo-test-"));
+        assert_eq!(project.feature, Some((4, None)));
+    }
+
+    #[test]
+    fn keys_outside_their_section_are_ignored() {
+        let godot = "config_version=5\n\n[rendering]\n\nconfig/name=\"Wrong\"\n";
+        let project = project(godot, None).unwrap();
+        assert!(project.name.starts_with("godo-test-"));
+    }
+
+    #[test]
+    fn pin_wins_over_features_and_keeps_mono() {
+        let godot = "config_version=5\n\n[application]\n\nconfig/features=PackedStringArray(\"4.2\", \"C#\")\n";
+        let project = project(godot, Some("4.3.1-rc2\n# comment\n")).unwrap();
+        let required = project.required().unwrap();
+        assert_eq!(
+            (required.major, required.minor, required.patch),
+            (4, Some(3), Some(1))
+        );
+        assert_eq!(required.pre, Some(PreRelease::Rc(2)));
+        assert_eq!(required.mono, Some(true));
+    }
+
+    #[test]
+    fn missing_project_file_is_no_project() {
+        assert!(Project::read(TempDir::new().path()).is_none());
+    }
+}
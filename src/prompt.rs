@@ -0,0 +1,167 @@
+use anyhow::{bail, Result};
+use colored::Colorize;
+use console::{Key, Term};
+use std::io::{IsTerminal, Write};
+
+/// Rows of the picker shown at once, longer lists scroll
+const PICKER_ROWS: usize = 10;
+
+/// How prompts are answered, decided once from `--yes`, `--no-input` and whether stdin is a TTY.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PromptMode {
+    #[default]
+    Interactive,
+    /// Every confirmation is answered with yes
+    AssumeYes,
+    /// Prompting is not possible, questions without a default fail
+    NoInput,
+}
+
+/// Single entry point for every interactive question godo asks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Prompter {
+    mode: PromptMode,
+}
+
+impl Prompter {
+    pub fn new(yes: bool, no_input: bool) -> Self {
+        let mode = if yes {
+            PromptMode::AssumeYes
+        } else if no_input || !std::io::stdin().is_terminal() {
+            PromptMode::NoInput
+        } else {
+            PromptMode::Interactive
+        };
+        Prompter { mode }
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        self.mode == PromptMode::Interactive
+    }
+
+    pub fn assumes_yes(&self) -> bool {
+        self.mode == PromptMode::AssumeYes
+    }
+
+    /// Asks a yes/no question. `flag` names what answers it without a prompt.
+    pub fn confirm(&self, prompt: &str, flag: &str) -> Result<bool> {
+        match self.mode {
+            PromptMode::Interactive => ask_yes_no(prompt),
+            PromptMode::AssumeYes => Ok(true),
+            PromptMode::NoInput => {
+                bail!("Cannot ask \"{prompt}\" without a terminal. Pass {flag} to answer it.")
+            }
+        }
+    }
+
+    /// Asks whether the Mono build is meant. `action` is the verb of the command, e.g. "Remove".
+    /// There is no safe default, so non-interactive runs fail unless `--mono` is given.
+    pub fn choose_mono(&self, action: &str) -> Result<bool> {
+        let prompt = format!("{action} the Mono version?");
+        match self.mode {
+            PromptMode::Interactive => ask_yes_no(&prompt),
+            PromptMode::AssumeYes | PromptMode::NoInput => bail!(
+                "Cannot decide \"{prompt}\" without a prompt. Pass --mono or --mono=false, or set default_mono."
+            ),
+        }
+    }
+
+    /// Lets the user pick one of `labels` with the arrow keys, typing filters the list.
+    /// `styled` holds the coloured rendering of each label. Returns `None` on Esc.
+    pub fn pick(
+        &self,
+        prompt: &str,
+        labels: &[String],
+        styled: &[String],
+    ) -> Result<Option<usize>> {
+        if !self.is_interactive() {
+            bail!("Cannot show a picker for \"{prompt}\" without a terminal");
+        }
+        let term = Term::stdout();
+        term.hide_cursor()?;
+        let picked = run_picker(&term, prompt, labels, styled);
+        term.show_cursor()?;
+        Ok(picked?)
+    }
+}
+
+fn run_picker(
+    term: &Term,
+    prompt: &str,
+    labels: &[String],
+    styled: &[String],
+) -> std::io::Result<Option<usize>> {
+    let mut filter = String::new();
+    let mut selected = 0;
+    let mut drawn = 0;
+
+    loop {
+        let visible: Vec<usize> = (0..labels.len())
+            .filter(|&i| fuzzy_match(&filter, &labels[i]))
+            .collect();
+        selected = selected.min(visible.len().saturating_sub(1));
+        let first = selected.saturating_sub(PICKER_ROWS - 1);
+
+        term.clear_last_lines(drawn)?;
+        term.write_line(&format!(
+            "  {prompt} {}",
+            if filter.is_empty() {
+                "(type to filter, Esc to cancel)".dimmed().to_string()
+            } else {
+                filter.bold().to_string()
+            }
+        ))?;
+        if visible.is_empty() {
+            term.write_line(&format!("    {}", "No match".dimmed()))?;
+        }
+        for (row, &i) in visible.iter().enumerate().skip(first).take(PICKER_ROWS) {
+            let cursor = if row == selected {
+                ">".bold()
+            } else {
+                " ".normal()
+            };
+            term.write_line(&format!("  {cursor} {}", styled[i]))?;
+        }
+        drawn = 1 + visible.len().clamp(1, PICKER_ROWS);
+
+        match term.read_key()? {
+            Key::ArrowUp => selected = selected.saturating_sub(1),
+            Key::ArrowDown if selected + 1 < visible.len() => selected += 1,
+            Key::Enter => {
+                if let Some(&i) = visible.get(selected) {
+                    term.clear_last_lines(drawn)?;
+                    return Ok(Some(i));
+                }
+            }
+            Key::Escape | Key::CtrlC => {
+                term.clear_last_lines(drawn)?;
+                return Ok(None);
+            }
+            Key::Backspace => {
+                filter.pop();
+            }
+            Key::Char(c) if !c.is_control() => filter.push(c),
+            _ => {}
+        }
+    }
+}
+
+/// Whether every character of `filter` appears in `label` in order, ignoring case.
+fn fuzzy_match(filter: &str, label: &str) -> bool {
+    let mut chars = label.chars().flat_map(char::to_lowercase);
+    filter
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|f| chars.any(|c| c == f))
+}
+
+fn ask_yes_no(prompt: &str) -> Result<bool> {
+    print!("  {prompt} [Y/n] ");
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    if std::io::stdin().read_line(&mut input)? == 0 {
+        bail!("No answer to \"{prompt}\", stdin was closed");
+    }
+    let answer = input.trim().to_lowercase();
+    Ok(answer.is_empty() || answer == "y" || answer == "yes")
+}
@@ -0,0 +1,265 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::trash::{copy_dir, now};
+use crate::version::GodotVersion;
+
+/// Marker files that make Godot keep its settings in `editor_data` next to the executable
+const SC_MARKERS: [&str; 2] = ["._sc_", "_sc_"];
+
+/// Folders of the editor config directory that are copied along with the settings
+const SETTINGS_DIRS: [&str; 3] = ["script_templates", "feature_profiles", "text_editor_themes"];
+
+const BACKUP_META: &str = "backup.toml";
+
+pub fn is_self_contained(version_dir: &Path) -> bool {
+    SC_MARKERS.iter().any(|m| version_dir.join(m).exists())
+}
+
+/// Creates the self-contained marker and `editor_data`. macOS builds check the
+/// directory of `Godot.app` too, so the version directory works on every platform.
+pub fn set_self_contained(version_dir: &Path, enable: bool) -> Result<()> {
+    if enable {
+        std::fs::write(version_dir.join(SC_MARKERS[0]), "")
+            .context("Failed to create self-contained marker")?;
+        std::fs::create_dir_all(version_dir.join("editor_data"))
+            .context("Failed to create editor_data")?;
+    } else {
+        for marker in SC_MARKERS {
+            let path = version_dir.join(marker);
+            if path.exists() {
+                std::fs::remove_file(&path).context("Failed to remove self-contained marker")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Directory the editor settings of an install live in: its `editor_data` when it is
+/// self-contained, the shared Godot config directory otherwise.
+pub fn location(config: &Config, version: &GodotVersion) -> Result<PathBuf> {
+    let version_dir = config.engine_dir.join(version.folder_name());
+    if is_self_contained(&version_dir) {
+        return Ok(version_dir.join("editor_data"));
+    }
+    crate::project::editor_config_dir().context("Cannot determine the Godot config directory")
+}
+
+/// `editor_settings-4.tres` up to Godot 4.2, one file per minor version such as
+/// `editor_settings-4.3.tres` since then.
+pub fn settings_file(version: &GodotVersion) -> String {
+    if version.major < 4 || (version.major == 4 && version.minor < 3) {
+        format!("editor_settings-{}.tres", version.major)
+    } else {
+        format!("editor_settings-{}.{}.tres", version.major, version.minor)
+    }
+}
+
+/// Files and folders that make up the settings of `version`, relative to its location
+fn items(version: &GodotVersion) -> Vec<String> {
+    let mut items = vec![
+        settings_file(version),
+        format!("editor_layouts-{}.cfg", version.major),
+    ];
+    items.extend(SETTINGS_DIRS.iter().map(|d| d.to_string()));
+    items
+}
+
+/// Top-level keys of the `[resource]` section of a settings file and their raw values.
+/// Values spanning several lines, like shortcuts, are kept whole.
+pub fn read_keys(path: &Path) -> Result<BTreeMap<String, String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut keys = BTreeMap::new();
+    let mut in_resource = false;
+    let mut current: Option<String> = None;
+    for line in content.lines() {
+        let is_header = [
+            "[gd_resource",
+            "[ext_resource",
+            "[sub_resource",
+            "[resource]",
+        ]
+        .iter()
+        .any(|h| line.starts_with(h));
+        if is_header {
+            in_resource = line.trim() == "[resource]";
+            current = None;
+            continue;
+        }
+        if !in_resource {
+            continue;
+        }
+        let starts_key = !line.starts_with(char::is_whitespace) && line.contains(" = ");
+        match line.split_once(" = ") {
+            Some((key, value)) if starts_key && !key.contains(['{', '}', '"', '[']) => {
+                keys.insert(key.to_string(), value.to_string());
+                current = Some(key.to_string());
+            }
+            _ => {
+                if let Some(value) = current.as_ref().and_then(|k| keys.get_mut(k)) {
+                    value.push('\n');
+                    value.push_str(line);
+                }
+            }
+        }
+    }
+    Ok(keys)
+}
+
+/// Copies the settings of `from` over those of `to`. The settings file is renamed to what
+/// `to` expects, the other files and folders are merged.
+pub fn copy(from: &GodotVersion, from_dir: &Path, to: &GodotVersion, to_dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(to_dir)?;
+    for (src, dst) in items(from).iter().zip(items(to)) {
+        let src = from_dir.join(src);
+        let dst = to_dir.join(dst);
+        if src == dst || !src.exists() {
+            continue;
+        }
+        copy_item(&src, &dst)?;
+    }
+    Ok(())
+}
+
+/// Copies a settings file or folder, merging folders into what is already there.
+fn copy_item(src: &Path, dst: &Path) -> Result<()> {
+    if src.is_dir() {
+        copy_dir(src, dst).with_context(|| format!("Failed to copy {}", src.display()))
+    } else {
+        std::fs::copy(src, dst)
+            .map(|_| ())
+            .with_context(|| format!("Failed to copy {}", src.display()))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupMeta {
+    /// Folder name of the install, see `GodotVersion::folder_name`
+    pub version: String,
+    /// Where the settings were copied from and are restored to
+    pub source: PathBuf,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+#[derive(Debug)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub meta: BackupMeta,
+}
+
+impl Backup {
+    pub fn id(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn version(&self) -> Option<GodotVersion> {
+        GodotVersion::from_folder(&self.meta.version)
+    }
+}
+
+fn backups_dir() -> PathBuf {
+    Config::data_dir().join("settings-backups")
+}
+
+/// Snapshots the settings of an install. Returns `None` when it has no settings yet.
+pub fn backup(config: &Config, version: &GodotVersion) -> Result<Option<Backup>> {
+    let source = location(config, version)?;
+    let present: Vec<String> = items(version)
+        .into_iter()
+        .filter(|i| source.join(i).exists())
+        .collect();
+    if present.is_empty() {
+        return Ok(None);
+    }
+
+    let meta = BackupMeta {
+        version: version.folder_name(),
+        source: source.clone(),
+        created_at: now(),
+    };
+    let mut path = backups_dir().join(format!("{}-{}", meta.created_at, meta.version));
+    let mut n = 1;
+    while path.exists() {
+        n += 1;
+        path = backups_dir().join(format!("{}-{}-{n}", meta.created_at, meta.version));
+    }
+    std::fs::create_dir_all(&path).context("Failed to create backup directory")?;
+
+    for item in &present {
+        copy_item(&source.join(item), &path.join(item))?;
+    }
+    std::fs::write(
+        path.join(BACKUP_META),
+        toml::to_string(&meta).context("Failed to serialize backup metadata")?,
+    )
+    .context("Failed to write backup metadata")?;
+
+    Ok(Some(Backup { path, meta }))
+}
+
+/// Every settings backup, newest first
+pub fn backups() -> Result<Vec<Backup>> {
+    let dir = backups_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(&dir).context("Failed to read backup directory")? {
+        let path = entry?.path();
+        let Ok(content) = std::fs::read_to_string(path.join(BACKUP_META)) else {
+            continue;
+        };
+        if let Ok(meta) = toml::from_str(&content) {
+            backups.push(Backup { path, meta });
+        }
+    }
+    backups.sort_by(|a, b| {
+        b.meta
+            .created_at
+            .cmp(&a.meta.created_at)
+            .then(b.path.cmp(&a.path))
+    });
+    Ok(backups)
+}
+
+/// Puts a backup back where it was taken from. The settings of the install are cleared
+/// first, so files created after the backup do not survive the restore.
+pub fn restore(backup: &Backup) -> Result<()> {
+    let target = &backup.meta.source;
+    std::fs::create_dir_all(target)?;
+
+    let mut names: Vec<String> = Vec::new();
+    for entry in std::fs::read_dir(&backup.path)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        if name != BACKUP_META {
+            names.push(name);
+        }
+    }
+    let mut cleared = backup.version().map(|v| items(&v)).unwrap_or_default();
+    cleared.extend(names.iter().cloned());
+    for item in &cleared {
+        let path = target.join(item);
+        let Ok(meta) = path.symlink_metadata() else {
+            continue;
+        };
+        let removed = if meta.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        removed.with_context(|| format!("Failed to clear {}", path.display()))?;
+    }
+
+    for name in &names {
+        copy_item(&backup.path.join(name), &target.join(name))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::trash::{copy_dir, now};
+use crate::version::GodotVersion;
+
+const SNAPSHOT_META: &str = "snapshot.toml";
+
+/// Where Godot 4 keeps the metadata of a project
+const META_DIR: &str = ".godot";
+
+/// Caches inside `.godot` that Godot rebuilds by itself, left out of snapshots
+const CACHE_DIRS: [&str; 2] = ["imported", "shader_cache"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotMeta {
+    pub project: PathBuf,
+    /// Folder name of the engine the project was about to be opened with
+    pub engine: String,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+#[derive(Debug)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub meta: SnapshotMeta,
+}
+
+impl Snapshot {
+    pub fn id(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+fn snapshots_dir() -> PathBuf {
+    Config::data_dir().join("snapshots")
+}
+
+/// Saves `project.godot` and the `.godot` metadata of a project, without the import caches.
+pub fn create(project: &Path, engine: &GodotVersion) -> Result<Snapshot> {
+    let meta = SnapshotMeta {
+        project: project.to_path_buf(),
+        engine: engine.folder_name(),
+        created_at: now(),
+    };
+    let name = project
+        .file_name()
+        .map(|n| n.to_string_lossy().replace(' ', "_"))
+        .unwrap_or_else(|| "project".to_string());
+    let mut path = snapshots_dir().join(format!("{}-{name}", meta.created_at));
+    let mut n = 1;
+    while path.exists() {
+        n += 1;
+        path = snapshots_dir().join(format!("{}-{name}-{n}", meta.created_at));
+    }
+    std::fs::create_dir_all(&path).context("Failed to create snapshot directory")?;
+
+    // A half-written snapshot has no metadata and would never be listed or cleaned up
+    if let Err(e) = write_snapshot(project, &path, &meta) {
+        let _ = std::fs::remove_dir_all(&path);
+        return Err(e);
+    }
+    Ok(Snapshot { path, meta })
+}
+
+fn write_snapshot(project: &Path, path: &Path, meta: &SnapshotMeta) -> Result<()> {
+    std::fs::copy(project.join("project.godot"), path.join("project.godot"))
+        .context("Failed to snapshot project.godot")?;
+    let meta_dir = project.join(META_DIR);
+    if meta_dir.is_dir() {
+        for entry in std::fs::read_dir(&meta_dir)? {
+            let entry = entry?;
+            if CACHE_DIRS.iter().any(|c| entry.file_name() == *c) {
+                continue;
+            }
+            let dest = path.join(META_DIR).join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                copy_dir(&entry.path(), &dest)?;
+            } else {
+                std::fs::create_dir_all(path.join(META_DIR))?;
+                std::fs::copy(entry.path(), &dest)
+                    .with_context(|| format!("Failed to snapshot {}", entry.path().display()))?;
+            }
+        }
+    }
+
+    std::fs::write(
+        path.join(SNAPSHOT_META),
+        toml::to_string(meta).context("Failed to serialize snapshot metadata")?,
+    )
+    .context("Failed to write snapshot metadata")
+}
+
+/// Every project snapshot, newest first
+pub fn snapshots() -> Result<Vec<Snapshot>> {
+    let dir = snapshots_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = Vec::new();
+    for entry in std::fs::read_dir(&dir).context("Failed to read snapshot directory")? {
+        let path = entry?.path();
+        let Ok(content) = std::fs::read_to_string(path.join(SNAPSHOT_META)) else {
+            continue;
+        };
+        if let Ok(meta) = toml::from_str(&content) {
+            snapshots.push(Snapshot { path, meta });
+        }
+    }
+    snapshots.sort_by(|a, b| {
+        b.meta
+            .created_at
+            .cmp(&a.meta.created_at)
+            .then(b.path.cmp(&a.path))
+    });
+    Ok(snapshots)
+}
+
+/// Puts `project.godot` and the `.godot` metadata of a snapshot back. Metadata written since
+/// is removed, and so are the import caches, which the newer engine may have rewritten in a
+/// format the snapshot's engine can't read. It imports everything again on the next start.
+pub fn restore(snapshot: &Snapshot) -> Result<()> {
+    let project = &snapshot.meta.project;
+    std::fs::copy(
+        snapshot.path.join("project.godot"),
+        project.join("project.godot"),
+    )
+    .context("Failed to restore project.godot")?;
+
+    let meta_dir = project.join(META_DIR);
+    if meta_dir.is_dir() {
+        for entry in std::fs::read_dir(&meta_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                std::fs::remove_dir_all(entry.path())?;
+            } else {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+    }
+    let saved = snapshot.path.join(META_DIR);
+    if !saved.is_dir() {
+        return Ok(());
+    }
+    copy_dir(&saved, &meta_dir).context("Failed to restore the .godot folder")
+}
@@ -0,0 +1,192 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, SourceKind};
+use crate::github::{self, GithubAsset, GithubRelease};
+
+const GITHUB_API: &str = "https://api.github.com";
+const OFFICIAL_REPOS: [&str; 2] = ["godotengine/godot", "godotengine/godot-builds"];
+
+/// A place where Godot releases and their assets can be discovered.
+pub trait ReleaseSource {
+    /// Namespace of the versions from this source, `None` for official releases
+    fn namespace(&self) -> Option<&str>;
+
+    fn fetch_releases(&self) -> Result<Vec<GithubRelease>>;
+}
+
+/// Returns the official GitHub source followed by every `[[sources]]` entry.
+pub fn sources(config: &Config) -> Vec<Box<dyn ReleaseSource>> {
+    let mut sources: Vec<Box<dyn ReleaseSource>> = vec![Box::new(GithubSource {
+        name: None,
+        api_url: GITHUB_API.to_string(),
+        repos: OFFICIAL_REPOS.iter().map(|r| r.to_string()).collect(),
+        token: config.github_token.clone(),
+    })];
+
+    for source in &config.sources {
+        let name = source.name.clone();
+        let token = source.token.clone();
+        let boxed: Box<dyn ReleaseSource> = match &source.kind {
+            SourceKind::Github { repo, api_url } => Box::new(GithubSource {
+                name: Some(name),
+                api_url: api_url.clone().unwrap_or_else(|| GITHUB_API.to_string()),
+                repos: vec![repo.clone()],
+                token,
+            }),
+            SourceKind::Gitea { url, repo } => Box::new(GiteaSource {
+                name,
+                url: url.clone(),
+                repo: repo.clone(),
+                token,
+            }),
+            SourceKind::Json { url } => Box::new(JsonIndexSource {
+                name,
+                url: url.clone(),
+            }),
+            SourceKind::Local { path } => Box::new(LocalDirSource {
+                name,
+                path: path.clone(),
+            }),
+        };
+        sources.push(boxed);
+    }
+
+    sources
+}
+
+/// GitHub or GitHub Enterprise releases of one or more repositories.
+pub struct GithubSource {
+    name: Option<String>,
+    api_url: String,
+    repos: Vec<String>,
+    token: Option<String>,
+}
+
+impl ReleaseSource for GithubSource {
+    fn namespace(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn fetch_releases(&self) -> Result<Vec<GithubRelease>> {
+        let auth = self.token.as_ref().map(|t| format!("Bearer {t}"));
+        let mut releases = Vec::new();
+        for repo in &self.repos {
+            let url = format!(
+                "{}/repos/{repo}/releases",
+                self.api_url.trim_end_matches('/')
+            );
+            releases.extend(github::fetch_release_pages(
+                self.name.as_deref().unwrap_or(github::OFFICIAL_SERVICE),
+                &url,
+                "per_page",
+                100,
+                auth.as_deref(),
+            )?);
+        }
+        Ok(releases)
+    }
+}
+
+/// Releases of a repository on a Gitea or Forgejo instance.
+pub struct GiteaSource {
+    name: String,
+    url: String,
+    repo: String,
+    token: Option<String>,
+}
+
+impl ReleaseSource for GiteaSource {
+    fn namespace(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn fetch_releases(&self) -> Result<Vec<GithubRelease>> {
+        let url = format!(
+            "{}/api/v1/repos/{}/releases",
+            self.url.trim_end_matches('/'),
+            self.repo
+        );
+        let auth = self.token.as_ref().map(|t| format!("token {t}"));
+        github::fetch_release_pages(&self.name, &url, "limit", 50, auth.as_deref())
+    }
+}
+
+/// A static JSON file in the GitHub releases format, served over HTTP or read from disk.
+pub struct JsonIndexSource {
+    name: String,
+    url: String,
+}
+
+impl ReleaseSource for JsonIndexSource {
+    fn namespace(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn fetch_releases(&self) -> Result<Vec<GithubRelease>> {
+        let content = if self.url.starts_with("http://") || self.url.starts_with("https://") {
+            let mut response = crate::http::agent()
+                .get(&self.url)
+                .call()
+                .context("Failed to fetch release index")?;
+            if !response.status().is_success() {
+                anyhow::bail!("Release index returned {}", response.status());
+            }
+            response
+                .body_mut()
+                .read_to_string()
+                .context("Failed to read release index")?
+        } else {
+            let path = self.url.strip_prefix("file://").unwrap_or(&self.url);
+            std::fs::read_to_string(path).context("Failed to read release index")?
+        };
+        serde_json::from_str(&content).context("Failed to parse release index")
+    }
+}
+
+/// A directory with one sub-directory of archives per release tag, e.g. `4.3.1-patch2/*.zip`.
+pub struct LocalDirSource {
+    name: String,
+    path: PathBuf,
+}
+
+impl ReleaseSource for LocalDirSource {
+    fn namespace(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn fetch_releases(&self) -> Result<Vec<GithubRelease>> {
+        let mut releases = Vec::new();
+        let entries = std::fs::read_dir(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            if !entry.path().is_dir() {
+                continue;
+            }
+            releases.push(GithubRelease {
+                tag_name: entry.file_name().to_string_lossy().to_string(),
+                assets: local_assets(&entry.path())?,
+                source: None,
+            });
+        }
+        Ok(releases)
+    }
+}
+
+fn local_assets(dir: &Path) -> Result<Vec<GithubAsset>> {
+    let mut assets = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = std::fs::canonicalize(entry.path())?;
+        if !path.is_file() {
+            continue;
+        }
+        assets.push(GithubAsset {
+            name: entry.file_name().to_string_lossy().to_string(),
+            browser_download_url: format!("file://{}", path.display()),
+            size: entry.metadata()?.len(),
+        });
+    }
+    Ok(assets)
+}
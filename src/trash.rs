@@ -0,0 +1,148 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::version::GodotVersion;
+
+const META_FILE: &str = "trash.toml";
+const ENGINE_DIR: &str = "engine";
+/// Name of the trash inside the engine directory
+const TRASH_DIR: &str = ".trash";
+
+/// What is known about a removed install, stored next to its files
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrashMeta {
+    /// Folder name of the install, see `GodotVersion::folder_name`
+    pub version: String,
+    /// Seconds since the Unix epoch
+    pub removed_at: u64,
+    pub was_current: bool,
+}
+
+#[derive(Debug)]
+pub struct TrashEntry {
+    pub path: PathBuf,
+    pub meta: TrashMeta,
+}
+
+impl TrashEntry {
+    pub fn version(&self) -> Option<GodotVersion> {
+        GodotVersion::from_folder(&self.meta.version)
+    }
+
+    pub fn engine_dir(&self) -> PathBuf {
+        self.path.join(ENGINE_DIR)
+    }
+}
+
+/// Lives inside the engine directory, so removing an install is a rename on the same file
+/// system even when `engine_dir` is on another drive than the data directory.
+fn trash_dir(config: &Config) -> PathBuf {
+    config.engine_dir.join(TRASH_DIR)
+}
+
+/// Where older versions kept the trash, still read so its entries can be restored
+fn legacy_trash_dir() -> PathBuf {
+    Config::data_dir().join("trash")
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Moves an installed version into the trash.
+pub fn move_to_trash(config: &Config, version: &GodotVersion, was_current: bool) -> Result<()> {
+    let folder = version.folder_name();
+    let source = config.engine_dir.join(&folder);
+    let meta = TrashMeta {
+        version: folder.clone(),
+        removed_at: now(),
+        was_current,
+    };
+
+    let entry = trash_dir(config).join(format!("{}-{folder}", meta.removed_at));
+    std::fs::create_dir_all(&entry).context("Failed to create trash directory")?;
+    std::fs::write(
+        entry.join(META_FILE),
+        toml::to_string(&meta).context("Failed to serialize trash metadata")?,
+    )
+    .context("Failed to write trash metadata")?;
+
+    if let Err(e) = move_dir(&source, &entry.join(ENGINE_DIR)) {
+        let _ = std::fs::remove_dir_all(&entry);
+        return Err(e).with_context(|| format!("Failed to move {folder} to the trash"));
+    }
+    Ok(())
+}
+
+/// Every trashed install, newest removal first. Entries with unreadable metadata are skipped.
+pub fn entries(config: &Config) -> Result<Vec<TrashEntry>> {
+    let mut entries = Vec::new();
+    for dir in [trash_dir(config), legacy_trash_dir()] {
+        if !dir.exists() {
+            continue;
+        }
+        for entry in std::fs::read_dir(&dir).context("Failed to read trash directory")? {
+            let path = entry?.path();
+            let Ok(content) = std::fs::read_to_string(path.join(META_FILE)) else {
+                continue;
+            };
+            if let Ok(meta) = toml::from_str(&content) {
+                entries.push(TrashEntry { path, meta });
+            }
+        }
+    }
+    entries.sort_by_key(|e| std::cmp::Reverse(e.meta.removed_at));
+    Ok(entries)
+}
+
+/// Moves a trashed install back into the engine directory.
+pub fn restore(config: &Config, entry: &TrashEntry) -> Result<()> {
+    let target = config.engine_dir.join(&entry.meta.version);
+    if target.exists() {
+        bail!(
+            "{} is already installed at {}",
+            entry.meta.version,
+            target.display()
+        );
+    }
+    move_dir(&entry.engine_dir(), &target)?;
+    std::fs::remove_dir_all(&entry.path).context("Failed to clean up trash entry")?;
+    Ok(())
+}
+
+/// Renames `from` to `to`, copying across file systems when a rename is not possible.
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_dir(from, to)?;
+    std::fs::remove_dir_all(from)?;
+    Ok(())
+}
+
+/// Copies a directory recursively, merging into `to` if it exists.
+pub fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &dest)?;
+            #[cfg(not(unix))]
+            std::fs::copy(entry.path(), &dest)?;
+        } else {
+            std::fs::copy(entry.path(), &dest)?;
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,6 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt;
 
@@ -8,16 +11,20 @@ pub enum PreRelease {
     Beta(u32),
     Rc(u32),
     Dev(u32),
+    /// Any other label, e.g. `patch2` on a studio fork. Forks publish these as releases, so
+    /// they count as stable and sort above the plain stable build of the same version. Two
+    /// labels compare by their text, then by a trailing number: `patch2` < `patch10`.
+    Custom(String),
 }
 
 impl PreRelease {
     pub fn is_stable(&self) -> bool {
-        matches!(self, PreRelease::Stable)
+        matches!(self, PreRelease::Stable | PreRelease::Custom(_))
     }
 
     fn priority(&self) -> u32 {
         match self {
-            PreRelease::Stable => 5,
+            PreRelease::Stable | PreRelease::Custom(_) => 5,
             PreRelease::Rc(_) => 4,
             PreRelease::Beta(_) => 3,
             PreRelease::Alpha(_) => 2,
@@ -26,6 +33,28 @@ impl PreRelease {
     }
 }
 
+/// Release channel used to pick a version when the query has no pre-release label.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    #[default]
+    Stable,
+    Rc,
+    Beta,
+}
+
+impl Channel {
+    /// Whether a release of this pre-release kind is at least as stable as the channel.
+    pub fn accepts(&self, pre: &PreRelease) -> bool {
+        let lowest = match self {
+            Channel::Stable => PreRelease::Stable,
+            Channel::Rc => PreRelease::Rc(0),
+            Channel::Beta => PreRelease::Beta(0),
+        };
+        pre.priority() >= lowest.priority()
+    }
+}
+
 impl fmt::Display for PreRelease {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -34,6 +63,7 @@ impl fmt::Display for PreRelease {
             PreRelease::Beta(n) => write!(f, "beta{n}"),
             PreRelease::Rc(n) => write!(f, "rc{n}"),
             PreRelease::Dev(n) => write!(f, "dev{n}"),
+            PreRelease::Custom(label) => write!(f, "{label}"),
         }
     }
 }
@@ -52,6 +82,11 @@ impl Ord for PreRelease {
                 | (PreRelease::Beta(a), PreRelease::Beta(b))
                 | (PreRelease::Rc(a), PreRelease::Rc(b))
                 | (PreRelease::Dev(a), PreRelease::Dev(b)) => a.cmp(b),
+                (PreRelease::Custom(a), PreRelease::Custom(b)) => {
+                    split_number(a).cmp(&split_number(b))
+                }
+                (PreRelease::Stable, PreRelease::Custom(_)) => Ordering::Less,
+                (PreRelease::Custom(_), PreRelease::Stable) => Ordering::Greater,
                 _ => Ordering::Equal,
             },
             ord => ord,
@@ -59,6 +94,12 @@ impl Ord for PreRelease {
     }
 }
 
+/// Splits a custom label into its text and trailing number, e.g. `patch10` into `("patch", 10)`
+fn split_number(label: &str) -> (&str, u64) {
+    let text = label.trim_end_matches(|c: char| c.is_ascii_digit());
+    (text, label[text.len()..].parse().unwrap_or(0))
+}
+
 fn parse_pre(s: &str) -> Option<PreRelease> {
     match s {
         "stable" => Some(PreRelease::Stable),
@@ -70,6 +111,39 @@ fn parse_pre(s: &str) -> Option<PreRelease> {
     }
 }
 
+/// Like `parse_pre`, but accepts arbitrary alphanumeric labels used by non-official sources.
+fn parse_pre_custom(s: &str) -> Option<PreRelease> {
+    parse_pre(s).or_else(|| {
+        let valid = s.starts_with(|c: char| c.is_ascii_alphabetic())
+            && s.chars().all(|c| c.is_ascii_alphanumeric());
+        valid.then(|| PreRelease::Custom(s.to_string()))
+    })
+}
+
+/// Splits an optional `+<label>` build label off a version string.
+fn split_label(s: &str) -> (&str, Option<String>) {
+    match s.split_once('+') {
+        Some((rest, label)) => (rest, Some(label.to_string())),
+        None => (s, None),
+    }
+}
+
+/// Whether `label` can name a linked build: letters, digits, `_` and `.`, starting with a letter
+pub fn is_valid_label(label: &str) -> bool {
+    label.starts_with(|c: char| c.is_ascii_alphabetic())
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Splits an optional `<source>/` namespace off a version string.
+fn split_source(s: &str) -> (Option<String>, &str) {
+    match s.split_once('/') {
+        Some((source, rest)) => (Some(source.to_string()), rest),
+        None => (None, s),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GodotVersion {
     pub major: u32,
@@ -77,6 +151,10 @@ pub struct GodotVersion {
     pub patch: u32,
     pub pre: PreRelease,
     pub mono: bool,
+    /// Name of the release source, `None` for official Godot releases
+    pub source: Option<String>,
+    /// Build label of an engine linked from elsewhere, e.g. `steam` in `4.3.0-stable+steam`
+    pub label: Option<String>,
 }
 
 impl GodotVersion {
@@ -85,7 +163,13 @@ impl GodotVersion {
     }
 
     pub fn folder_name(&self) -> String {
-        let base = format!("{}.{}.{}-{}", self.major, self.minor, self.patch, self.pre);
+        let mut base = format!("{}.{}.{}-{}", self.major, self.minor, self.patch, self.pre);
+        if let Some(source) = &self.source {
+            base = format!("{source}@{base}");
+        }
+        if let Some(label) = &self.label {
+            base = format!("{base}+{label}");
+        }
         if self.mono {
             format!("{base}-mono")
         } else {
@@ -94,7 +178,14 @@ impl GodotVersion {
     }
 
     pub fn version_key(&self) -> String {
-        format!("{}.{}.{}-{}", self.major, self.minor, self.patch, self.pre)
+        let mut key = format!("{}.{}.{}-{}", self.major, self.minor, self.patch, self.pre);
+        if let Some(label) = &self.label {
+            key = format!("{key}+{label}");
+        }
+        match &self.source {
+            Some(source) => format!("{source}/{key}"),
+            None => key,
+        }
     }
 
     pub fn from_tag(tag: &str) -> Option<Self> {
@@ -117,12 +208,45 @@ impl GodotVersion {
             patch,
             pre,
             mono: false,
+            source: None,
+            label: None,
+        })
+    }
+
+    /// Parses a full version as typed by the user, e.g. `4.3-stable`, `studio/4.3.1-patch2`
+    /// or `4.4-dev3+mybuild`.
+    pub fn from_input(input: &str) -> Option<Self> {
+        let (input, label) = split_label(input.trim());
+        if label.as_deref().is_some_and(|l| !is_valid_label(l)) {
+            return None;
+        }
+        let version = match split_source(input) {
+            (Some(source), tag) => Self::from_source_tag(&source, tag),
+            (None, tag) => Self::from_tag(tag),
+        }?;
+        Some(GodotVersion { label, ..version })
+    }
+
+    /// Parses a tag published by a non-official source, which may carry custom labels.
+    pub fn from_source_tag(source: &str, tag: &str) -> Option<Self> {
+        let tag = tag.strip_prefix('v').unwrap_or(tag);
+        let (version_part, pre_part) = tag.split_once('-').unwrap_or((tag, "stable"));
+        let pre = parse_pre_custom(pre_part)?;
+        Some(GodotVersion {
+            pre,
+            source: Some(source.to_string()),
+            ..GodotVersion::from_tag(version_part)?
         })
     }
 
     pub fn from_folder(name: &str) -> Option<Self> {
+        let (source, name) = match name.split_once('@') {
+            Some((source, rest)) => (Some(source.to_string()), rest),
+            None => (None, name),
+        };
         let mono = name.ends_with("-mono");
         let name = if mono { &name[..name.len() - 5] } else { name };
+        let (name, label) = split_label(name);
 
         let (version_part, pre_part) = if let Some(idx) = name.find('-') {
             (&name[..idx], &name[idx + 1..])
@@ -130,7 +254,11 @@ impl GodotVersion {
             return None;
         };
 
-        let pre = parse_pre(pre_part)?;
+        let pre = if source.is_some() {
+            parse_pre_custom(pre_part)?
+        } else {
+            parse_pre(pre_part)?
+        };
         let parts: Vec<&str> = version_part.split('.').collect();
         let major = parts.first()?.parse().ok()?;
         let minor = parts.get(1)?.parse().ok()?;
@@ -142,17 +270,25 @@ impl GodotVersion {
             patch,
             pre,
             mono,
+            source,
+            label,
         })
     }
 }
 
 impl fmt::Display for GodotVersion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(source) = &self.source {
+            write!(f, "{source}/")?;
+        }
         write!(
             f,
             "{}.{}.{}-{}",
             self.major, self.minor, self.patch, self.pre
         )?;
+        if let Some(label) = &self.label {
+            write!(f, "+{label}")?;
+        }
         if self.mono {
             write!(f, "-mono")?;
         }
@@ -173,6 +309,8 @@ impl Ord for GodotVersion {
             .then_with(|| self.minor.cmp(&other.minor))
             .then_with(|| self.patch.cmp(&other.patch))
             .then_with(|| self.pre.cmp(&other.pre))
+            .then_with(|| self.source.cmp(&other.source))
+            .then_with(|| self.label.cmp(&other.label))
     }
 }
 
@@ -183,18 +321,58 @@ pub struct VersionQuery {
     pub patch: Option<u32>,
     pub pre: Option<PreRelease>,
     pub mono: Option<bool>,
+    pub source: Option<String>,
+    /// Only matches linked engines with this build label when set
+    pub label: Option<String>,
+}
+
+impl fmt::Display for VersionQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(source) = &self.source {
+            write!(f, "{source}/")?;
+        }
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{minor}")?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{patch}")?;
+        }
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        if let Some(label) = &self.label {
+            write!(f, "+{label}")?;
+        }
+        if self.mono == Some(true) {
+            write!(f, "-mono")?;
+        }
+        Ok(())
+    }
 }
 
 impl VersionQuery {
+    /// Parses what a user types, e.g. `4`, `4.3-rc2`, `studio/4.3`, `4.4+custom` or
+    /// `4.3-mono`. A `-mono` suffix asks for the Mono build, as in `Display`.
     pub fn from_input(input: &str) -> Option<Self> {
         let input = input.trim();
+        let (input, mono) = match input.strip_suffix("-mono") {
+            Some(input) => (input, Some(true)),
+            None => (input, None),
+        };
+        let (input, label) = split_label(input);
+        let (source, input) = split_source(input);
         let (version_part, pre_part) = if let Some(idx) = input.find('-') {
             (&input[..idx], Some(&input[idx + 1..]))
         } else {
             (input, None)
         };
 
-        let pre = pre_part.and_then(parse_pre);
+        let pre = if source.is_some() {
+            pre_part.and_then(parse_pre_custom)
+        } else {
+            pre_part.and_then(parse_pre)
+        };
         let parts: Vec<&str> = version_part.split('.').collect();
         let major = parts.first()?.parse().ok()?;
         let minor = parts.get(1).and_then(|s| s.parse().ok());
@@ -205,14 +383,22 @@ impl VersionQuery {
             minor,
             patch,
             pre,
-            mono: None,
+            mono,
+            source,
+            label,
         })
     }
 
     pub fn matches_loose(&self, version: &GodotVersion) -> bool {
+        if self.source != version.source {
+            return false;
+        }
         if self.major != version.major {
             return false;
         }
+        if self.label.is_some() && self.label != version.label {
+            return false;
+        }
         if let Some(minor) = self.minor {
             if minor != version.minor {
                 return false;
@@ -231,3 +417,173 @@ impl VersionQuery {
         true
     }
 }
+
+/// Versions given as a range, a list or both, e.g. `4.1..4.4`, `4.2,4.3` or `3.5,4.1..4.3`.
+/// Range bounds are inclusive, so `4.1..4.4` includes every 4.4 patch.
+#[derive(Debug, Clone)]
+pub struct VersionSet {
+    parts: Vec<(VersionQuery, Option<VersionQuery>)>,
+}
+
+impl VersionSet {
+    /// Parses a comma separated list of versions and ranges. Both bounds of a range must
+    /// name the same source, and the lower bound may not come after the upper one.
+    pub fn parse(input: &str) -> Result<Self> {
+        let query = |text: &str| {
+            VersionQuery::from_input(text).with_context(|| {
+                format!(
+                    "Invalid version '{}', use a range or a list such as 4.1..4.4 or 4.2,4.3",
+                    text.trim()
+                )
+            })
+        };
+        let mut parts = Vec::new();
+        for part in input.split(',') {
+            let Some((from, to)) = part.split_once("..") else {
+                parts.push((query(part)?, None));
+                continue;
+            };
+            let (from, to) = (query(from)?, query(to)?);
+            let part = part.trim();
+            if from.source != to.source {
+                bail!("The bounds of '{part}' name different sources");
+            }
+            if from.label.is_some() && to.label.is_some() && from.label != to.label {
+                bail!("The bounds of '{part}' name different labels");
+            }
+            if Self::lower_bound(&from) > Self::upper_bound(&to) {
+                bail!("'{part}' is empty, its lower bound comes after the upper one");
+            }
+            parts.push((from, Some(to)));
+        }
+        Ok(VersionSet { parts })
+    }
+
+    /// Whether a version is in the set. Like a single query, a range only filters by label
+    /// or Mono when one of its bounds names them.
+    pub fn contains(&self, version: &GodotVersion) -> bool {
+        self.parts.iter().any(|(from, to)| match to {
+            None => from.matches_loose(version),
+            Some(to) => {
+                let v = (version.major, version.minor, version.patch);
+                let label = from.label.as_ref().or(to.label.as_ref());
+                let mono = from.mono.or(to.mono);
+                version.source == from.source
+                    && label.is_none_or(|l| version.label.as_ref() == Some(l))
+                    && mono.is_none_or(|m| version.mono == m)
+                    && Self::lower_bound(from) <= v
+                    && v <= Self::upper_bound(to)
+            }
+        })
+    }
+
+    fn lower_bound(query: &VersionQuery) -> (u32, u32, u32) {
+        (
+            query.major,
+            query.minor.unwrap_or(0),
+            query.patch.unwrap_or(0),
+        )
+    }
+
+    /// Missing parts match everything, so `4.4` as an upper bound includes every 4.4 patch
+    fn upper_bound(query: &VersionQuery) -> (u32, u32, u32) {
+        (
+            query.major,
+            query.minor.unwrap_or(u32::MAX),
+            query.patch.unwrap_or(u32::MAX),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(folder: &str) -> GodotVersion {
+        GodotVersion::from_folder(folder).unwrap()
+    }
+
+    fn set(input: &str) -> VersionSet {
+        VersionSet::parse(input).unwrap()
+    }
+
+    #[test]
+    fn range_includes_every_patch_of_its_bounds() {
+        let range = set("4.1..4.3");
+        assert!(range.contains(&version("4.1.0-stable")));
+        assert!(range.contains(&version("4.2.2-rc1")));
+        assert!(range.contains(&version("4.3.5-stable")));
+        assert!(!range.contains(&version("4.0.4-stable")));
+        assert!(!range.contains(&version("4.4.0-stable")));
+        assert!(!range.contains(&version("5.1.0-stable")));
+    }
+
+    #[test]
+    fn range_bounds_with_patches_are_exact() {
+        let range = set("4.2.1..4.3.0");
+        assert!(!range.contains(&version("4.2.0-stable")));
+        assert!(range.contains(&version("4.2.1-stable")));
+        assert!(range.contains(&version("4.3.0-stable")));
+        assert!(!range.contains(&version("4.3.1-stable")));
+    }
+
+    #[test]
+    fn list_and_ranges_combine() {
+        let mixed = set("3.5, 4.2..4.3");
+        assert!(mixed.contains(&version("3.5.3-stable")));
+        assert!(mixed.contains(&version("4.2.2-stable")));
+        assert!(!mixed.contains(&version("3.6.0-stable")));
+        assert!(!mixed.contains(&version("4.1.0-stable")));
+    }
+
+    #[test]
+    fn range_treats_labels_like_a_list() {
+        let steam = version("4.3.0-stable+steam");
+        assert!(set("4.3").contains(&steam));
+        assert!(set("4.1..4.4").contains(&steam));
+
+        let labelled = set("4.1..4.4+steam");
+        assert!(labelled.contains(&steam));
+        assert!(!labelled.contains(&version("4.3.0-stable")));
+        assert!(!labelled.contains(&version("4.3.0-stable+mine")));
+    }
+
+    #[test]
+    fn range_filters_mono_only_when_asked() {
+        let mono = version("4.2.0-stable-mono");
+        assert!(set("4.1..4.3").contains(&mono));
+        assert!(set("4.1..4.3-mono").contains(&mono));
+        assert!(!set("4.1..4.3-mono").contains(&version("4.2.0-stable")));
+    }
+
+    #[test]
+    fn range_keeps_to_its_source() {
+        let fork = version("studio@4.2.0-patch2");
+        assert!(set("studio/4.1..studio/4.3").contains(&fork));
+        assert!(!set("studio/4.1..studio/4.3").contains(&version("4.2.0-stable")));
+        assert!(!set("4.1..4.3").contains(&fork));
+    }
+
+    #[test]
+    fn parse_rejects_bad_sets() {
+        assert!(VersionSet::parse("4.4..4.1").is_err());
+        assert!(VersionSet::parse("4.3.2..4.3.1").is_err());
+        assert!(VersionSet::parse("studio/4.1..4.3").is_err());
+        assert!(VersionSet::parse("4.1+a..4.3+b").is_err());
+        assert!(VersionSet::parse("4.1..latest").is_err());
+        assert!(VersionSet::parse("4.2,,4.3").is_err());
+        assert!(VersionSet::parse("").is_err());
+        assert!(VersionSet::parse("4.3..4.3").is_ok());
+        assert!(VersionSet::parse("4..4.2").is_ok());
+    }
+
+    #[test]
+    fn custom_labels_rank_above_stable_and_by_number() {
+        let stable = version("studio@4.3.0-stable");
+        let patch2 = version("studio@4.3.0-patch2");
+        let patch10 = version("studio@4.3.0-patch10");
+        assert!(stable < patch2);
+        assert!(patch2 < patch10);
+        assert!(version("4.3.0-rc2") < stable);
+    }
+}